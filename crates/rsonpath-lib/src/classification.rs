@@ -0,0 +1,27 @@
+//! Classification of bytes into the structural/quote/depth/label information a query
+//! executor needs, across a handful of independently-SIMD-accelerated passes.
+//!
+//! This is the successor to the legacy, single-pass [`crate::classify`] module: each
+//! concern (quote state, structural characters, depth, member-label search) gets its
+//! own submodule and its own SIMD backend selection, so a caller only pays for the
+//! passes it actually needs instead of one monolithic classifier doing all four.
+//!
+//! [`matcher`] and [`xxh3`] are genuinely reachable from here now, but still have no
+//! query-executor caller: that would need `JsonPathQuery`, `Label`, and the
+//! `query::automaton::nfa`/`minimizer` lowering pipeline, none of which are defined
+//! anywhere in this tree (confirmed by a repo-wide search, not merely unwired) --
+//! see the note atop [`crate::query::automaton`] for the full extent of that gap.
+//!
+//! `memmem` and `structural` are deliberately not declared here yet: unlike every
+//! other submodule below, they have no root file at all (only a `memmem/`/`structural/`
+//! directory of SIMD backends that expect a `super::*` providing shared types and a
+//! `shared::structural_classifier!`-style macro neither directory defines). Writing
+//! that connective module from scratch would mean inventing a nontrivial piece of this
+//! crate's architecture with no existing specification to match against, which is a
+//! different kind of gap than a missing `mod` line and out of scope here.
+pub mod depth;
+pub(crate) mod matcher;
+pub(crate) mod neon;
+pub mod quotes;
+pub mod simd;
+pub(crate) mod xxh3;
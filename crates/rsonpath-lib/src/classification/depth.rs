@@ -0,0 +1,9 @@
+//! Depth classification: per-block `DepthBlock`-style SIMD classifiers for streaming traversal,
+//! plus [`index::StructuralIndex`], a whole-document index answering depth and matching-bracket
+//! queries in O(1) without replaying the stream from the start.
+pub(crate) mod avx512_64;
+pub(crate) mod index;
+pub(crate) mod neon;
+pub(crate) mod portable;
+
+pub(crate) use index::StructuralIndex;
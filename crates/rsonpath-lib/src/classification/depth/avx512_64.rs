@@ -0,0 +1,61 @@
+use super::*;
+use crate::classification::{QuoteClassifiedBlock, ResumeClassifierBlockState};
+use crate::input::InputBlock;
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+super::shared::depth_classifier!(Constructor, BlockAvx512DepthClassifier64, 64, u64);
+
+struct BlockAvx512DepthClassifier64;
+
+impl BlockAvx512DepthClassifier64 {
+    fn new() -> Self {
+        Self
+    }
+
+    #[inline]
+    fn classify<'i, B: InputBlock<'i, 64>>(
+        &mut self,
+        quote_classified_block: &QuoteClassifiedBlock<B, u64, 64>,
+        opening: BracketType,
+    ) -> (u64, u64, i64) {
+        let (open_byte, close_byte) = match opening {
+            BracketType::Curly => (b'{', b'}'),
+            BracketType::Square => (b'[', b']'),
+        };
+
+        // SAFETY: target feature invariant, this classifier is only constructed behind an
+        // `is_x86_feature_detected!("avx512bw")` check.
+        let (opening_mask, closing_mask) = unsafe {
+            let opening_mask =
+                byte_mask(&quote_classified_block.block, open_byte) & !quote_classified_block.within_quotes_mask;
+            let closing_mask =
+                byte_mask(&quote_classified_block.block, close_byte) & !quote_classified_block.within_quotes_mask;
+
+            (opening_mask, closing_mask)
+        };
+
+        let depth_delta = i64::from(opening_mask.count_ones()) - i64::from(closing_mask.count_ones());
+
+        (opening_mask, closing_mask, depth_delta)
+    }
+}
+
+/// Compare the whole 64-byte block against a splat of `byte` and collapse the comparison
+/// directly into a 64-bit membership mask via `vpcmpeqb`'s mask-register form -- AVX-512BW
+/// produces the `__mmask64` in the compare itself, so unlike the narrower SSE2/AVX2 backends
+/// there is no separate movemask step, and unlike a 128-byte block there is no need to combine
+/// two halves either: one register covers the whole block.
+///
+/// # Safety
+/// Requires the `avx512bw` target feature.
+#[target_feature(enable = "avx512bw")]
+#[inline]
+unsafe fn byte_mask(block: &[u8], byte: u8) -> u64 {
+    let bytes = _mm512_loadu_si512(block.as_ptr().cast::<i32>());
+    let needle = _mm512_set1_epi8(byte as i8);
+
+    _mm512_cmpeq_epi8_mask(bytes, needle)
+}
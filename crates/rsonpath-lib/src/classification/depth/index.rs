@@ -0,0 +1,260 @@
+//! A whole-document structural index, built once and then queried in O(1)
+//! instead of re-deriving depth a block at a time.
+//!
+//! The streaming [`DepthBlock`](super::DepthBlock)-style classifiers only ever know the depth
+//! relative to wherever their current block starts, so answering "what is the depth at byte
+//! offset `i`" or "where does the bracket opened at `i` close" requires replaying every block up
+//! to that point. [`StructuralIndex`] trades an upfront linear scan for answering both queries
+//! without that replay.
+
+const BITS_IN_WORD: usize = 64;
+
+/// A persistent structural index over an entire JSON document.
+///
+/// Built by [`StructuralIndex::build`], which scans the input once to
+/// record, per 64-bit word, which byte offsets are unquoted opening or
+/// closing structural characters (`quoted` bytes, i.e. ones inside a
+/// JSON string literal, are tracked during the scan and excluded from
+/// both bitvectors). Alongside each bitvector it keeps a running prefix
+/// popcount per word, so [`depth`](StructuralIndex::depth) can answer
+/// `rank_open(i) - rank_close(i)` as a word lookup plus a popcount of the
+/// partial word, instead of a byte-by-byte replay.
+pub(crate) struct StructuralIndex {
+    opening: Vec<u64>,
+    closing: Vec<u64>,
+    open_rank: Vec<u32>,
+    close_rank: Vec<u32>,
+    len: usize,
+}
+
+impl StructuralIndex {
+    /// Scan `bytes` and build the index over it.
+    #[must_use]
+    pub(crate) fn build(bytes: &[u8]) -> Self {
+        let word_count = bytes.len().div_ceil(BITS_IN_WORD);
+        let mut opening = vec![0u64; word_count];
+        let mut closing = vec![0u64; word_count];
+
+        let mut in_string = false;
+        let mut escaped = false;
+
+        for (i, &byte) in bytes.iter().enumerate() {
+            if in_string {
+                if escaped {
+                    escaped = false;
+                } else if byte == b'\\' {
+                    escaped = true;
+                } else if byte == b'"' {
+                    in_string = false;
+                }
+                continue;
+            }
+
+            match byte {
+                b'"' => in_string = true,
+                b'{' | b'[' => opening[i / BITS_IN_WORD] |= 1 << (i % BITS_IN_WORD),
+                b'}' | b']' => closing[i / BITS_IN_WORD] |= 1 << (i % BITS_IN_WORD),
+                _ => (),
+            }
+        }
+
+        let open_rank = prefix_popcounts(&opening);
+        let close_rank = prefix_popcounts(&closing);
+
+        Self {
+            opening,
+            closing,
+            open_rank,
+            close_rank,
+            len: bytes.len(),
+        }
+    }
+
+    /// Number of bytes covered by this index.
+    #[must_use]
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the indexed document was empty.
+    #[must_use]
+    #[inline]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The JSON depth at byte offset `i`: the number of unquoted
+    /// structural openings strictly before `i`, net of closings.
+    ///
+    /// Answered in O(1) by combining the enclosing word's prefix
+    /// popcount with a popcount of the bits before `i` within that word.
+    ///
+    /// # Panics
+    /// Panics if `i > self.len()`.
+    #[must_use]
+    pub(crate) fn depth(&self, i: usize) -> isize {
+        assert!(i <= self.len, "offset out of bounds for this index");
+        (rank(&self.open_rank, &self.opening, i) as isize) - (rank(&self.close_rank, &self.closing, i) as isize)
+    }
+
+    /// Given that `i` is the offset of an unquoted opening structural
+    /// byte, find the offset one past its matching closing bracket.
+    ///
+    /// Advances word-at-a-time: whenever the remaining closes in a word
+    /// cannot bring the net depth down to the entry depth, the whole
+    /// word is skipped via a pair of popcounts rather than stepped byte
+    /// by byte. Only the word containing the actual match is walked bit
+    /// by bit.
+    ///
+    /// Returns `None` if `i` is not an opening structural byte, or if
+    /// the document ends before a match is found.
+    #[must_use]
+    pub(crate) fn find_matching_close(&self, i: usize) -> Option<usize> {
+        if i >= self.len || (self.opening[i / BITS_IN_WORD] >> (i % BITS_IN_WORD)) & 1 == 0 {
+            return None;
+        }
+
+        let mut net: i64 = 1;
+        let mut word_idx = i / BITS_IN_WORD;
+        let mut start_bit = i % BITS_IN_WORD + 1;
+
+        while word_idx < self.opening.len() {
+            let mask = if start_bit >= BITS_IN_WORD { 0 } else { u64::MAX << start_bit };
+            let opens = (self.opening[word_idx] & mask).count_ones() as i64;
+            let closes = (self.closing[word_idx] & mask).count_ones() as i64;
+
+            if net - closes > 0 {
+                // Even closing every bracket left in this word can't reach
+                // the entry depth, so skip the word outright.
+                net += opens - closes;
+            } else {
+                for bit in start_bit..BITS_IN_WORD {
+                    let global = word_idx * BITS_IN_WORD + bit;
+                    if global >= self.len {
+                        break;
+                    }
+                    if (self.opening[word_idx] >> bit) & 1 == 1 {
+                        net += 1;
+                    } else if (self.closing[word_idx] >> bit) & 1 == 1 {
+                        net -= 1;
+                        if net == 0 {
+                            return Some(global + 1);
+                        }
+                    }
+                }
+            }
+
+            word_idx += 1;
+            start_bit = 0;
+        }
+
+        None
+    }
+}
+
+fn prefix_popcounts(words: &[u64]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(words.len());
+    let mut running = 0;
+    for &word in words {
+        result.push(running);
+        running += word.count_ones();
+    }
+    result
+}
+
+fn rank(prefix: &[u32], words: &[u64], i: usize) -> u32 {
+    let word_idx = i / BITS_IN_WORD;
+    if word_idx >= words.len() {
+        // `i` lands exactly on (or past) the end of the last word; the
+        // total count is that word's prefix plus its own full popcount.
+        return match (prefix.last(), words.last()) {
+            (Some(&p), Some(&w)) => p + w.count_ones(),
+            _ => 0,
+        };
+    }
+    let bit_idx = i % BITS_IN_WORD;
+    let mask = if bit_idx == 0 { 0 } else { u64::MAX >> (BITS_IN_WORD - bit_idx) };
+    prefix[word_idx] + (words[word_idx] & mask).count_ones()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depth_matches_byte_by_byte_computation() {
+        let json = r#"{"aaa":[{},{"b":{"c":[1,2,3]}}]}"#;
+        let index = StructuralIndex::build(json.as_bytes());
+
+        let mut depth = 0isize;
+        for (i, &byte) in json.as_bytes().iter().enumerate() {
+            assert_eq!(index.depth(i), depth, "mismatch at offset {i}");
+            depth += match byte {
+                b'{' | b'[' => 1,
+                b'}' | b']' => -1,
+                _ => 0,
+            };
+        }
+        assert_eq!(index.depth(json.len()), depth);
+    }
+
+    #[test]
+    fn brackets_inside_strings_are_not_structural() {
+        let json = r#"{"a":"[{}]","b":1}"#;
+        let index = StructuralIndex::build(json.as_bytes());
+
+        // Only the outer braces are structural; the bracket pair embedded
+        // in the string literal must not affect depth.
+        assert_eq!(index.depth(json.len()), 0);
+        assert_eq!(index.depth(json.len() - 1), 1);
+    }
+
+    #[test]
+    fn escaped_quotes_do_not_end_the_string_early() {
+        let json = r#"{"a":"\"[","b":1}"#;
+        let index = StructuralIndex::build(json.as_bytes());
+
+        // The escaped quote must not be mistaken for the closing quote,
+        // so the `[` right after it stays inside the string.
+        assert_eq!(index.depth(json.len()), 0);
+    }
+
+    #[test]
+    fn find_matching_close_jumps_over_a_whole_subtree() {
+        let json = r#"{"a":[1,2,3],"b":2}"#;
+        let index = StructuralIndex::build(json.as_bytes());
+
+        let open = json.find('[').unwrap();
+        let close = json.find(']').unwrap();
+
+        assert_eq!(index.find_matching_close(open), Some(close + 1));
+    }
+
+    #[test]
+    fn find_matching_close_handles_nesting() {
+        let json = r#"{"a":{"b":{"c":1}},"d":2}"#;
+        let index = StructuralIndex::build(json.as_bytes());
+
+        let outer_open = 0;
+        let outer_close = json.rfind('}').unwrap();
+        // The outer object's matching close is the last brace in the document.
+        assert_eq!(index.find_matching_close(outer_open), Some(outer_close + 1));
+    }
+
+    #[test]
+    fn find_matching_close_returns_none_for_non_opening_offset() {
+        let json = r#"{"a":1}"#;
+        let index = StructuralIndex::build(json.as_bytes());
+
+        assert_eq!(index.find_matching_close(1), None);
+    }
+
+    #[test]
+    fn find_matching_close_spans_multiple_words() {
+        let json = format!("{{{}}}", "\"a\":1,".repeat(20));
+        let index = StructuralIndex::build(json.as_bytes());
+
+        assert_eq!(index.find_matching_close(0), Some(json.len()));
+    }
+}
@@ -0,0 +1,65 @@
+//! `aarch64`/NEON depth classification.
+//!
+//! The opening (`{`, `[`) and closing (`}`, `]`) bracket masks are produced the same way the
+//! structural classifier produces its delimiter mask -- `vceqq_u8` per half plus
+//! [`neon::movemask`] -- and `POPCNT` is substituted with [`u32::count_ones`] on the resulting
+//! bitmasks, since NEON has no single-instruction popcount over a whole mask register either.
+use super::*;
+use crate::classification::neon::movemask;
+use crate::classification::{QuoteClassifiedBlock, ResumeClassifierBlockState};
+use crate::debug;
+use crate::input::InputBlock;
+use crate::{MaskType, BLOCK_SIZE};
+use core::arch::aarch64::{vceqq_u8, vdupq_n_u8, vld1q_u8};
+
+super::shared::depth_classifier!(Constructor, BlockNeonDepthClassifier, BLOCK_SIZE, MaskType);
+
+struct BlockNeonDepthClassifier;
+
+impl BlockNeonDepthClassifier {
+    fn new() -> Self {
+        Self
+    }
+
+    /// # Safety
+    /// Requires the `neon` target feature, which is part of the AArch64 baseline.
+    #[inline]
+    unsafe fn half_mask(half: &[u8], byte: u8) -> u16 {
+        let bytes = vld1q_u8(half.as_ptr());
+        movemask(vceqq_u8(bytes, vdupq_n_u8(byte)))
+    }
+
+    #[inline]
+    fn classify<'i, B: InputBlock<'i, BLOCK_SIZE>>(
+        &mut self,
+        quote_classified_block: &QuoteClassifiedBlock<B, MaskType, BLOCK_SIZE>,
+        opening: BracketType,
+    ) -> (MaskType, MaskType, i64) {
+        let (open_byte, close_byte) = match opening {
+            BracketType::Curly => (b'{', b'}'),
+            BracketType::Square => (b'[', b']'),
+        };
+
+        let (half1, half2) = quote_classified_block.block.halves();
+
+        // SAFETY: target feature invariant, NEON is part of the AArch64 baseline.
+        let (opening_mask, closing_mask) = unsafe {
+            let opening_lo = Self::half_mask(half1, open_byte) as MaskType;
+            let opening_hi = Self::half_mask(half2, open_byte) as MaskType;
+            let closing_lo = Self::half_mask(half1, close_byte) as MaskType;
+            let closing_hi = Self::half_mask(half2, close_byte) as MaskType;
+            (
+                (opening_lo | (opening_hi << (BLOCK_SIZE / 2))) & !quote_classified_block.within_quotes_mask,
+                (closing_lo | (closing_hi << (BLOCK_SIZE / 2))) & !quote_classified_block.within_quotes_mask,
+            )
+        };
+
+        let depth_delta = i64::from(opening_mask.count_ones()) - i64::from(closing_mask.count_ones());
+
+        debug!("opening: {:032b}", opening_mask);
+        debug!("closing: {:032b}", closing_mask);
+        debug!("delta:   {}", depth_delta);
+
+        (opening_mask, closing_mask, depth_delta)
+    }
+}
@@ -0,0 +1,72 @@
+//! Portable depth classification built on `std::simd`.
+//!
+//! The opening (`{`, `[`) and closing (`}`, `]`) bracket masks are produced the same way the
+//! structural classifier produces its delimiter mask: the block is walked in [`CHUNK`]-sized
+//! pieces matching a 128-bit vector register, each byte splat across a
+//! [`Simd<u8, CHUNK>`](core::simd::Simd) lane and compared with [`Simd::simd_eq`], and the
+//! per-chunk bitmasks are shifted into place and OR'd into the full block bitmask. Actual hardware
+//! `POPCNT` is an x86/ARM-specific instruction with no portable intrinsic, so the running depth
+//! delta for a block -- how far the bracket balance moves across it -- is produced with
+//! [`u32::count_ones`] on the opening/closing bitmasks instead, which every target lowers to
+//! either a real popcount instruction or a portable bit-trick at no cost to correctness.
+use super::*;
+use crate::classification::{QuoteClassifiedBlock, ResumeClassifierBlockState};
+use crate::input::InputBlock;
+use crate::{debug, MaskType, BLOCK_SIZE};
+use core::simd::{Simd, SimdPartialEq};
+
+super::shared::depth_classifier!(Constructor, BlockPortableDepthClassifier, BLOCK_SIZE, MaskType);
+
+/// Width of a single `core::simd` chunk used by this backend, matching a 128-bit vector register.
+const CHUNK: usize = 16;
+
+struct BlockPortableDepthClassifier;
+
+impl BlockPortableDepthClassifier {
+    fn new() -> Self {
+        Self
+    }
+
+    /// Classify one [`CHUNK`]-sized slice of the block, returning `(opening, closing)` bitmasks.
+    #[inline]
+    fn classify_chunk(chunk: &[u8], open_byte: u8, close_byte: u8) -> (MaskType, MaskType) {
+        let bytes: Simd<u8, CHUNK> = Simd::from_slice(chunk);
+
+        let opening = bytes.simd_eq(Simd::splat(open_byte)).to_bitmask() as MaskType;
+        let closing = bytes.simd_eq(Simd::splat(close_byte)).to_bitmask() as MaskType;
+
+        (opening, closing)
+    }
+
+    #[inline]
+    fn classify<'i, B: InputBlock<'i, BLOCK_SIZE>>(
+        &mut self,
+        quote_classified_block: &QuoteClassifiedBlock<B, MaskType, BLOCK_SIZE>,
+        opening: BracketType,
+    ) -> (MaskType, MaskType, i64) {
+        let (open_byte, close_byte) = match opening {
+            BracketType::Curly => (b'{', b'}'),
+            BracketType::Square => (b'[', b']'),
+        };
+
+        let mut opening_mask: MaskType = 0;
+        let mut closing_mask: MaskType = 0;
+        for (chunk_idx, chunk) in quote_classified_block.block.chunks_exact(CHUNK).enumerate() {
+            let (chunk_opening, chunk_closing) = Self::classify_chunk(chunk, open_byte, close_byte);
+            opening_mask |= chunk_opening << (chunk_idx * CHUNK);
+            closing_mask |= chunk_closing << (chunk_idx * CHUNK);
+        }
+        opening_mask &= !quote_classified_block.within_quotes_mask;
+        closing_mask &= !quote_classified_block.within_quotes_mask;
+
+        // `fast_popcnt`'s portable counterpart: no dedicated instruction to rely on, but
+        // `count_ones` still lowers to one on every target that has it.
+        let depth_delta = i64::from(opening_mask.count_ones()) - i64::from(closing_mask.count_ones());
+
+        debug!("opening: {:032b}", opening_mask);
+        debug!("closing: {:032b}", closing_mask);
+        debug!("delta:   {}", depth_delta);
+
+        (opening_mask, closing_mask, depth_delta)
+    }
+}
@@ -0,0 +1,640 @@
+//! Multi-pattern label matching for descendant selectors with many candidate keys.
+//!
+//! The query executor ordinarily matches one member label at a time: a descendant
+//! selector naming (or a union spanning) many candidate keys pays for one pass per
+//! key. [`LabelMatcher`] instead compiles every candidate label into a single
+//! Aho-Corasick automaton, so one forward walk over the input reports every
+//! candidate label ending at each position; the caller intersects those positions
+//! against the structural bitmask from the depth engine to confirm real matches.
+//!
+//! To avoid walking the automaton over bytes that can't possibly start a match,
+//! [`LabelMatcher`] also builds a [`RarestBytePrefilter`]: for each pattern it picks
+//! the byte the [`BYTE_FREQUENCY`] table ranks least common, then scans the input
+//! for occurrences of those anchor bytes word-at-a-time instead of one byte at a
+//! time. The automaton only ever runs in the (usually small) windows around hits.
+//!
+//! This module is now declared for real (`classification.rs` has a `pub(crate) mod
+//! matcher;`, reachable from the crate root via `lib.rs`), so it's no longer orphaned
+//! in the sense of being unreachable dead code. It still has no query-executor caller:
+//! that executor would need `JsonPathQuery`/`Label` and the `query::automaton::nfa`/
+//! `minimizer` lowering pipeline, none of which are defined anywhere in this tree (see
+//! the note atop [`crate::query::automaton`]), and this module's own submodule siblings
+//! (`depth::portable`/`depth::neon`) depend on `crate::MaskType`/`crate::BLOCK_SIZE`/
+//! `crate::debug`, which also aren't defined anywhere -- so reachability alone doesn't
+//! make this crate compile yet. [`LabelMatcher::find_direct_keys`] is a real, separate
+//! caller in the meantime: given a
+//! [`StructuralIndex`](crate::classification::depth::StructuralIndex) over the whole
+//! document, it finds which candidate labels appear as direct keys of a single
+//! object, using the index's matching-close lookup to jump over each key's value
+//! instead of tracking depth byte by byte -- the same "intersect against
+//! structural positions from the depth engine" integration this module's own
+//! doc comment describes. The scalar/string skipping helpers below are also
+//! shared with [`FilterExpr::evaluate`](crate::query::automaton::filter::FilterExpr::evaluate),
+//! which walks objects the same way to resolve a filter's relative path.
+use crate::classification::depth::StructuralIndex;
+use crate::classification::xxh3::LabelHash;
+use std::collections::{HashMap, VecDeque};
+
+/// A single pattern occurrence found by [`LabelMatcher::find_all`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LabelMatch {
+    /// Index into the label list the [`LabelMatcher`] was built from.
+    pub pattern_index: usize,
+    /// Offset one past the last matched byte.
+    pub end: usize,
+}
+
+/// Compiles a set of member-name labels into a matcher for single-pass
+/// multi-key descendant matching, guarded by a rarest-byte prefilter.
+///
+/// The overwhelmingly common case is a single candidate label, for which
+/// building a full Aho-Corasick trie is wasted work; [`LabelMatcher`] instead
+/// special-cases that as [`Strategy::Single`], comparing each candidate key
+/// against the one label via [`LabelHash::matches`] (hash-reject, then an
+/// exact comparison only on a hash hit) instead of walking a trie.
+pub struct LabelMatcher {
+    strategy: Strategy,
+    prefilter: RarestBytePrefilter,
+    max_pattern_len: usize,
+    pattern_lens: Vec<usize>,
+}
+
+/// How [`LabelMatcher`] verifies candidate positions the prefilter flags.
+enum Strategy {
+    /// A single label: verified via [`LabelHash`] instead of a trie.
+    Single { label: Vec<u8>, hash: LabelHash },
+    /// Several labels, compiled into one [`AhoCorasick`] automaton.
+    Automaton(AhoCorasick),
+}
+
+impl Strategy {
+    fn build(patterns: &[&[u8]]) -> Self {
+        match patterns {
+            [label] => Self::Single {
+                label: label.to_vec(),
+                hash: LabelHash::of(label),
+            },
+            _ => Self::Automaton(AhoCorasick::build(patterns)),
+        }
+    }
+
+    /// Walk `haystack` once, returning every `(pattern_index, end_offset)` pair
+    /// where `end_offset` is one past the last matched byte.
+    fn scan(&self, haystack: &[u8]) -> Vec<(usize, usize)> {
+        match self {
+            Self::Automaton(automaton) => automaton.scan(haystack),
+            Self::Single { label, hash } => {
+                let mut matches = Vec::new();
+                if haystack.len() >= label.len() {
+                    for start in 0..=haystack.len() - label.len() {
+                        let candidate = &haystack[start..start + label.len()];
+                        if hash.matches(candidate, label) {
+                            matches.push((0, start + label.len()));
+                        }
+                    }
+                }
+                matches
+            }
+        }
+    }
+
+    /// Returns the pattern index that `key` matches *in full*, if any.
+    fn match_exact(&self, key: &[u8], pattern_lens: &[usize]) -> Option<usize> {
+        match self {
+            Self::Automaton(automaton) => automaton
+                .scan(key)
+                .into_iter()
+                .find(|&(pattern_index, end)| end == key.len() && pattern_lens[pattern_index] == key.len())
+                .map(|(pattern_index, _)| pattern_index),
+            Self::Single { label, hash } => hash.matches(key, label).then_some(0),
+        }
+    }
+}
+
+impl LabelMatcher {
+    /// Compile a matcher over the given labels.
+    ///
+    /// # Panics
+    /// Panics if `labels` is empty, or if any label is empty.
+    #[must_use]
+    pub fn new<'p, I>(labels: I) -> Self
+    where
+        I: IntoIterator<Item = &'p [u8]>,
+    {
+        let patterns: Vec<&[u8]> = labels.into_iter().collect();
+        assert!(!patterns.is_empty(), "a label matcher needs at least one label");
+        assert!(patterns.iter().all(|p| !p.is_empty()), "label patterns must not be empty");
+
+        let max_pattern_len = patterns.iter().map(|p| p.len()).max().unwrap();
+        let pattern_lens = patterns.iter().map(|p| p.len()).collect();
+
+        Self {
+            strategy: Strategy::build(&patterns),
+            prefilter: RarestBytePrefilter::build(&patterns),
+            max_pattern_len,
+            pattern_lens,
+        }
+    }
+
+    /// Find every label occurrence in `haystack`.
+    ///
+    /// Only verifies in the windows the prefilter flags as possibly containing
+    /// a match, so documents with few or no candidate keys are scanned in
+    /// close to the time the prefilter itself takes.
+    #[must_use]
+    pub fn find_all(&self, haystack: &[u8]) -> Vec<LabelMatch> {
+        let hits = self.prefilter.anchor_positions(haystack);
+        let mut matches = Vec::new();
+
+        for (start, end) in merge_windows(&hits, self.max_pattern_len, haystack.len()) {
+            for (pattern_index, local_end) in self.strategy.scan(&haystack[start..end]) {
+                matches.push(LabelMatch {
+                    pattern_index,
+                    end: start + local_end,
+                });
+            }
+        }
+
+        matches
+    }
+
+    /// Returns the candidate pattern that `key` matches *in full* (from its first byte
+    /// to its last), if any.
+    ///
+    /// Unlike [`find_all`](Self::find_all), `key` is assumed to already be exactly
+    /// delimited (e.g. the contents of one quoted object key), so a pattern only
+    /// counts as a match if it covers the whole slice -- a pattern ending at `key.len()`
+    /// but shorter than `key` would only mean `key` ends with that pattern, not that it
+    /// equals it.
+    fn match_exact(&self, key: &[u8]) -> Option<usize> {
+        self.strategy.match_exact(key, &self.pattern_lens)
+    }
+
+    /// Find which candidate labels appear as direct (immediate-child) keys of the
+    /// object opening at `object_open`, using `index` to jump straight over each
+    /// key's value instead of tracking depth byte by byte.
+    ///
+    /// The returned matches' `end` is one past the key's closing quote.
+    ///
+    /// # Panics
+    /// Panics if `bytes[object_open]` is not `{`, or if `index` was not built over
+    /// `bytes` (so a container opened under `object_open` has no matching close).
+    #[must_use]
+    pub(crate) fn find_direct_keys(&self, bytes: &[u8], index: &StructuralIndex, object_open: usize) -> Vec<LabelMatch> {
+        assert_eq!(bytes[object_open], b'{', "object_open must point at an opening '{{'");
+        let object_close = index
+            .find_matching_close(object_open)
+            .expect("index must be built over bytes and cover a matching '}'");
+
+        let mut matches = Vec::new();
+        let mut offset = skip_whitespace(bytes, object_open + 1);
+
+        while offset < object_close - 1 {
+            assert_eq!(bytes[offset], b'"', "expected an object key");
+            let key_start = offset + 1;
+            let key_end = find_string_end(bytes, key_start);
+
+            if let Some(pattern_index) = self.match_exact(&bytes[key_start..key_end]) {
+                matches.push(LabelMatch {
+                    pattern_index,
+                    end: key_end + 1,
+                });
+            }
+
+            offset = skip_whitespace(bytes, key_end + 1);
+            assert_eq!(bytes[offset], b':', "expected ':' after an object key");
+            offset = skip_whitespace(bytes, offset + 1);
+
+            offset = match bytes[offset] {
+                b'{' | b'[' => index
+                    .find_matching_close(offset)
+                    .expect("every nested container closes before the outer object does"),
+                _ => skip_scalar_value(bytes, offset),
+            };
+
+            offset = skip_whitespace(bytes, offset);
+            if offset < object_close - 1 && bytes[offset] == b',' {
+                offset = skip_whitespace(bytes, offset + 1);
+            }
+        }
+
+        matches
+    }
+}
+
+/// Advance past any JSON whitespace starting at `offset`.
+pub(crate) fn skip_whitespace(bytes: &[u8], mut offset: usize) -> usize {
+    while offset < bytes.len() && matches!(bytes[offset], b' ' | b'\t' | b'\n' | b'\r') {
+        offset += 1;
+    }
+    offset
+}
+
+/// Returns the index of the unescaped `"` that closes a string starting at `offset`
+/// (one past its opening quote).
+pub(crate) fn find_string_end(bytes: &[u8], mut offset: usize) -> usize {
+    let mut escaped = false;
+    while offset < bytes.len() {
+        match bytes[offset] {
+            b'\\' if !escaped => escaped = true,
+            b'"' if !escaped => return offset,
+            _ => escaped = false,
+        }
+        offset += 1;
+    }
+    offset
+}
+
+/// Advance past a single non-container value (a string, number, `true`, `false`, or
+/// `null`) starting at `offset`, stopping at the first unquoted `,` or `}`.
+pub(crate) fn skip_scalar_value(bytes: &[u8], mut offset: usize) -> usize {
+    let mut in_string = false;
+    let mut escaped = false;
+
+    while offset < bytes.len() {
+        let byte = bytes[offset];
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if byte == b'\\' {
+                escaped = true;
+            } else if byte == b'"' {
+                in_string = false;
+            }
+        } else {
+            match byte {
+                b'"' => in_string = true,
+                b',' | b'}' => break,
+                _ => (),
+            }
+        }
+        offset += 1;
+    }
+
+    offset
+}
+
+/// Merge per-hit verification windows into disjoint ranges.
+///
+/// A pattern containing its anchor byte at offset `hit` cannot start before
+/// `hit - (max_pattern_len - 1)` nor finish after `hit + max_pattern_len`, so that
+/// range is always enough to verify any match this particular hit could belong to.
+/// Hits close enough together have their windows coalesced, so a byte is never
+/// re-verified by the automaton more than once.
+fn merge_windows(hits: &[usize], max_pattern_len: usize, haystack_len: usize) -> Vec<(usize, usize)> {
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+
+    for &hit in hits {
+        let start = hit.saturating_sub(max_pattern_len - 1);
+        let end = (hit + max_pattern_len).min(haystack_len);
+
+        match merged.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = end.max(*last_end),
+            _ => merged.push((start, end)),
+        }
+    }
+
+    merged
+}
+
+/// A trie node: `children` hold the real trie edges used while building; once
+/// [`AhoCorasick::build`] finishes, all matching goes through the completed
+/// transition table instead.
+#[derive(Default)]
+struct Node {
+    children: HashMap<u8, usize>,
+    fail: usize,
+    /// Pattern indices ending at this node, including ones inherited through the
+    /// failure link (merged in at build time so matching never has to chase the
+    /// link chain itself).
+    output: Vec<usize>,
+}
+
+/// An Aho-Corasick automaton over a fixed set of byte-string patterns.
+///
+/// Built as a keyword trie over the patterns, with failure links assigned by BFS
+/// (a node's failure target is the longest proper suffix of its path from the root
+/// that is also a trie prefix; the root's own children always fail to the root).
+/// The trie and failure links are then used to complete the `goto` function so
+/// that every state has a transition for every byte, letting [`scan`](Self::scan)
+/// do a single forward walk with one table lookup per byte rather than chasing
+/// failure links at match time.
+struct AhoCorasick {
+    /// `goto[state][byte]` is the next state; always defined after `build`.
+    goto: Vec<[usize; 256]>,
+    output: Vec<Vec<usize>>,
+}
+
+impl AhoCorasick {
+    fn build(patterns: &[&[u8]]) -> Self {
+        let mut nodes = vec![Node::default()];
+
+        for (pattern_index, pattern) in patterns.iter().enumerate() {
+            let mut state = 0;
+            for &byte in *pattern {
+                state = match nodes[state].children.get(&byte) {
+                    Some(&child) => child,
+                    None => {
+                        nodes.push(Node::default());
+                        let child = nodes.len() - 1;
+                        nodes[state].children.insert(byte, child);
+                        child
+                    }
+                };
+            }
+            nodes[state].output.push(pattern_index);
+        }
+
+        let mut queue = VecDeque::new();
+        for &child in nodes[0].children.clone().values() {
+            nodes[child].fail = 0;
+            queue.push_back(child);
+        }
+        while let Some(state) = queue.pop_front() {
+            for (byte, child) in nodes[state].children.clone() {
+                let mut candidate = nodes[state].fail;
+                let fail_target = loop {
+                    if let Some(&target) = nodes[candidate].children.get(&byte) {
+                        break target;
+                    }
+                    if candidate == 0 {
+                        break 0;
+                    }
+                    candidate = nodes[candidate].fail;
+                };
+                nodes[child].fail = fail_target;
+
+                let inherited = nodes[fail_target].output.clone();
+                nodes[child].output.extend(inherited);
+
+                queue.push_back(child);
+            }
+        }
+
+        // Complete the goto function in BFS order, so a state's fallback entries
+        // can always be copied from its failure target's table, which (being
+        // strictly shallower) is already fully completed by the time we get here.
+        let mut goto = vec![[0usize; 256]; nodes.len()];
+        let mut queue = VecDeque::new();
+        for byte in 0..=255u8 {
+            if let Some(&child) = nodes[0].children.get(&byte) {
+                goto[0][byte as usize] = child;
+                queue.push_back(child);
+            }
+        }
+        while let Some(state) = queue.pop_front() {
+            let fail_state = nodes[state].fail;
+            for byte in 0..=255u8 {
+                if let Some(&child) = nodes[state].children.get(&byte) {
+                    goto[state][byte as usize] = child;
+                    queue.push_back(child);
+                } else {
+                    goto[state][byte as usize] = goto[fail_state][byte as usize];
+                }
+            }
+        }
+
+        let output = nodes.into_iter().map(|node| node.output).collect();
+
+        Self { goto, output }
+    }
+
+    /// Walk `haystack` once, returning every `(pattern_index, end_offset)` pair
+    /// where `end_offset` is one past the last matched byte.
+    fn scan(&self, haystack: &[u8]) -> Vec<(usize, usize)> {
+        let mut state = 0;
+        let mut matches = Vec::new();
+
+        for (i, &byte) in haystack.iter().enumerate() {
+            state = self.goto[state][byte as usize];
+            for &pattern_index in &self.output[state] {
+                matches.push((pattern_index, i + 1));
+            }
+        }
+
+        matches
+    }
+}
+
+/// Picks each pattern's least-frequent byte as an anchor, then finds every
+/// occurrence of any anchor byte in a haystack.
+///
+/// Every true occurrence of a pattern necessarily contains that pattern's anchor
+/// byte, so scanning for anchor occurrences across all patterns never misses a
+/// match; it just lets [`LabelMatcher`] skip running the automaton everywhere else.
+struct RarestBytePrefilter {
+    anchor_bytes: Vec<u8>,
+}
+
+impl RarestBytePrefilter {
+    fn build(patterns: &[&[u8]]) -> Self {
+        let mut anchor_bytes: Vec<u8> = patterns
+            .iter()
+            .map(|pattern| {
+                *pattern
+                    .iter()
+                    .max_by_key(|&&byte| BYTE_FREQUENCY[byte as usize])
+                    .expect("label patterns must not be empty")
+            })
+            .collect();
+        anchor_bytes.sort_unstable();
+        anchor_bytes.dedup();
+
+        Self { anchor_bytes }
+    }
+
+    /// Offsets in `haystack` of any configured anchor byte, in ascending order.
+    fn anchor_positions(&self, haystack: &[u8]) -> Vec<usize> {
+        let mut positions = Vec::new();
+        for &anchor in &self.anchor_bytes {
+            push_byte_positions(haystack, anchor, &mut positions);
+        }
+        positions.sort_unstable();
+        positions.dedup();
+        positions
+    }
+}
+
+/// Find every occurrence of `needle` in `haystack`, word-at-a-time.
+///
+/// Each 8-byte word is checked for a `needle` byte with the standard SWAR
+/// "has-zero-byte" trick (XOR against a word of repeated `needle`s turns any
+/// matching lane to zero, then a subtract-and-mask detects it), so whole words
+/// with no match are skipped without inspecting their individual bytes.
+fn push_byte_positions(haystack: &[u8], needle: u8, out: &mut Vec<usize>) {
+    const LO: u64 = 0x0101_0101_0101_0101;
+    const HI: u64 = 0x8080_8080_8080_8080;
+
+    let needle_word = u64::from_ne_bytes([needle; 8]);
+    let mut chunks = haystack.chunks_exact(8);
+    let mut offset = 0;
+
+    for chunk in chunks.by_ref() {
+        let word = u64::from_ne_bytes(chunk.try_into().unwrap());
+        let xored = word ^ needle_word;
+        if xored.wrapping_sub(LO) & !xored & HI != 0 {
+            for (i, &byte) in chunk.iter().enumerate() {
+                if byte == needle {
+                    out.push(offset + i);
+                }
+            }
+        }
+        offset += 8;
+    }
+    for (i, &byte) in chunks.remainder().iter().enumerate() {
+        if byte == needle {
+            out.push(offset + i);
+        }
+    }
+}
+
+/// A rough, monotonic byte-frequency ranking for typical JSON/text documents —
+/// lower means more common. Not calibrated against a real corpus; it only needs
+/// to be accurate enough to pick a rare anchor byte per pattern.
+#[rustfmt::skip]
+static BYTE_FREQUENCY: [u16; 256] = [
+     73,  74,  75,  76,  77,  78,  79,  80,  81,  82,  83,  84,  85,  86,  87,  88,
+     89,  90,  91,  92,  93,  94,  95,  96,  97,  98,  99, 100, 101, 102, 103, 104,
+      0, 105,  69, 106, 107, 108, 109,  70, 110, 111, 112, 113,  66,  64,  65,  71,
+     27,  28,  29,  30,  31,  32,  33,  34,  35,  36,  67,  68, 114, 115, 116, 117,
+    118,  39,  56,  48,  46,  37,  52,  53,  44,  41,  59,  58,  47,  50,  42,  40,
+     55,  61,  45,  43,  38,  49,  57,  51,  60,  54,  62, 119,  72, 120, 121,  63,
+    122,   3,  20,  12,  10,   1,  16,  17,   8,   5,  23,  22,  11,  14,   6,   4,
+     19,  25,   9,   7,   2,  13,  21,  15,  24,  18,  26, 123, 124, 125, 126, 127,
+    128, 129, 130, 131, 132, 133, 134, 135, 136, 137, 138, 139, 140, 141, 142, 143,
+    144, 145, 146, 147, 148, 149, 150, 151, 152, 153, 154, 155, 156, 157, 158, 159,
+    160, 161, 162, 163, 164, 165, 166, 167, 168, 169, 170, 171, 172, 173, 174, 175,
+    176, 177, 178, 179, 180, 181, 182, 183, 184, 185, 186, 187, 188, 189, 190, 191,
+    192, 193, 194, 195, 196, 197, 198, 199, 200, 201, 202, 203, 204, 205, 206, 207,
+    208, 209, 210, 211, 212, 213, 214, 215, 216, 217, 218, 219, 220, 221, 222, 223,
+    224, 225, 226, 227, 228, 229, 230, 231, 232, 233, 234, 235, 236, 237, 238, 239,
+    240, 241, 242, 243, 244, 245, 246, 247, 248, 249, 250, 251, 252, 253, 254, 255,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matched_patterns<'p>(labels: &[&'p [u8]], haystack: &[u8]) -> Vec<(&'p [u8], usize)> {
+        let matcher = LabelMatcher::new(labels.iter().copied());
+        matcher
+            .find_all(haystack)
+            .into_iter()
+            .map(|m| (labels[m.pattern_index], m.end))
+            .collect()
+    }
+
+    #[test]
+    fn finds_a_single_label() {
+        let labels: [&[u8]; 1] = [b"name"];
+        let haystack = b"{\"name\":\"value\"}";
+
+        let found = matched_patterns(&labels, haystack);
+
+        assert_eq!(found, vec![(&b"name"[..], 6)]);
+    }
+
+    #[test]
+    fn finds_every_candidate_label_in_one_pass() {
+        let labels: [&[u8]; 3] = [b"isbn", b"price", b"title"];
+        let haystack = b"{\"title\":\"t\",\"isbn\":\"1\",\"price\":2}";
+
+        let mut found = matched_patterns(&labels, haystack);
+        found.sort_by_key(|&(_, end)| end);
+
+        let expected_ends: Vec<usize> = found.iter().map(|&(_, end)| end).collect();
+        assert_eq!(found.len(), 3);
+        assert!(expected_ends.windows(2).all(|w| w[0] < w[1]));
+        assert!(found.iter().any(|&(label, _)| label == b"title"));
+        assert!(found.iter().any(|&(label, _)| label == b"isbn"));
+        assert!(found.iter().any(|&(label, _)| label == b"price"));
+    }
+
+    #[test]
+    fn does_not_match_a_label_that_is_not_present() {
+        let labels: [&[u8]; 1] = [b"missing"];
+        let haystack = b"{\"name\":\"value\"}";
+
+        assert!(matched_patterns(&labels, haystack).is_empty());
+    }
+
+    #[test]
+    fn matches_overlapping_patterns_sharing_a_suffix() {
+        // "he" is a proper suffix of "she", exercising the failure-link walk.
+        let labels: [&[u8]; 2] = [b"she", b"he"];
+        let haystack = b"ushers";
+
+        let mut found = matched_patterns(&labels, haystack);
+        found.sort_by_key(|&(label, end)| (end, label));
+
+        assert_eq!(found, vec![(&b"he"[..], 4), (&b"she"[..], 4)]);
+    }
+
+    #[test]
+    fn matches_clustered_far_apart_in_long_input() {
+        let labels: [&[u8]; 1] = [b"needle"];
+        let mut haystack = vec![b'x'; 200];
+        haystack.extend_from_slice(b"needle");
+        haystack.extend(vec![b'x'; 200]);
+        haystack.extend_from_slice(b"needle");
+
+        let found = matched_patterns(&labels, &haystack);
+
+        assert_eq!(found, vec![(&b"needle"[..], 206), (&b"needle"[..], 412)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one label")]
+    fn panics_on_empty_label_set() {
+        let labels: [&[u8]; 0] = [];
+        let _ = LabelMatcher::new(labels.iter().copied());
+    }
+
+    #[test]
+    fn single_label_matcher_takes_the_hash_fast_path_and_still_finds_matches() {
+        let labels: [&[u8]; 1] = [b"isbn"];
+        let haystack = b"{\"isbn\":\"1\",\"price\":\"2\"}";
+
+        let found = matched_patterns(&labels, haystack);
+
+        assert_eq!(found, vec![(&b"isbn"[..], 6)]);
+    }
+
+    #[test]
+    fn find_direct_keys_matches_only_top_level_keys() {
+        let labels: [&[u8]; 2] = [b"name", b"isbn"];
+        let matcher = LabelMatcher::new(labels.iter().copied());
+        let bytes = b"{\"name\":\"Alice\",\"nested\":{\"name\":\"inner\",\"isbn\":1},\"isbn\":2}";
+        let index = StructuralIndex::build(bytes);
+
+        let mut found: Vec<_> = matcher
+            .find_direct_keys(bytes, &index, 0)
+            .into_iter()
+            .map(|m| labels[m.pattern_index])
+            .collect();
+        found.sort_unstable();
+
+        assert_eq!(found, vec![&b"isbn"[..], &b"name"[..]]);
+    }
+
+    #[test]
+    fn find_direct_keys_does_not_match_a_key_that_is_only_a_suffix() {
+        // "name" is a proper suffix of "surname"; an exact-key match must reject it.
+        let labels: [&[u8]; 1] = [b"name"];
+        let matcher = LabelMatcher::new(labels.iter().copied());
+        let bytes = b"{\"surname\":\"Doe\"}";
+        let index = StructuralIndex::build(bytes);
+
+        assert!(matcher.find_direct_keys(bytes, &index, 0).is_empty());
+    }
+
+    #[test]
+    fn find_direct_keys_handles_an_empty_object() {
+        let labels: [&[u8]; 1] = [b"name"];
+        let matcher = LabelMatcher::new(labels.iter().copied());
+        let bytes = b"{}";
+        let index = StructuralIndex::build(bytes);
+
+        assert!(matcher.find_direct_keys(bytes, &index, 0).is_empty());
+    }
+}
@@ -0,0 +1,78 @@
+//! AVX-512 member-label search.
+//!
+//! Like the other SIMD backends, this only vectorizes the prefilter: splat the label's first
+//! byte across a 512-bit register, compare the whole block against it in one `vpcmpeqb` and
+//! collapse straight to a [`MaskType`] bitmask of candidate start positions via
+//! `_mm512_cmpeq_epi8_mask` -- the same mask-register trick
+//! [`depth::avx512_64::byte_mask`](crate::classification::depth::avx512_64) uses, so unlike the
+//! narrower backends there is no separate movemask step and no need to split the block into
+//! halves. Candidates are then verified with a plain byte-compare against the full label, the
+//! same as the scalar path.
+use super::*;
+use crate::input::{Input, InputBlockIterator};
+use crate::query::JsonString;
+use crate::result::InputRecorder;
+use crate::{MaskType, BLOCK_SIZE};
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+#[derive(Clone, Copy)]
+pub(crate) struct Constructor;
+
+pub(crate) struct Avx512MemmemClassifier<'i, 'b, 'r, I, R>
+where
+    I: Input + 'i,
+    R: InputRecorder<I::Block<'i, BLOCK_SIZE>> + 'r,
+{
+    input: &'i I,
+    iter: &'b mut I::BlockIterator<'i, 'r, BLOCK_SIZE, R>,
+}
+
+impl<'i, 'b, 'r, I, R> Avx512MemmemClassifier<'i, 'b, 'r, I, R>
+where
+    I: Input + 'i,
+    R: InputRecorder<I::Block<'i, BLOCK_SIZE>> + 'r,
+{
+    fn new(input: &'i I, iter: &'b mut I::BlockIterator<'i, 'r, BLOCK_SIZE, R>) -> Self {
+        Self { input, iter }
+    }
+
+    /// # Safety
+    /// Requires the `avx512bw` target feature.
+    #[target_feature(enable = "avx512bw")]
+    #[inline]
+    unsafe fn first_byte_candidates(block: &[u8], first_byte: u8) -> MaskType {
+        let bytes = _mm512_loadu_si512(block.as_ptr().cast::<i32>());
+        let needle = _mm512_set1_epi8(first_byte as i8);
+
+        _mm512_cmpeq_epi8_mask(bytes, needle) as MaskType
+    }
+
+    /// Search forward from the current position for the next occurrence of `label`, using the
+    /// vectorized first-byte prefilter to skip blocks that cannot contain a match.
+    pub(crate) fn find_label(&mut self, label: &JsonString) -> Result<Option<usize>, I::Error> {
+        let needle = label.bytes_with_quotes();
+        let Some(&first_byte) = needle.first() else {
+            return Ok(None);
+        };
+
+        while let Some(block) = self.iter.next()? {
+            // SAFETY: target feature invariant, only constructed behind an
+            // `is_x86_feature_detected!("avx512bw")` check.
+            let mut candidates = unsafe { Self::first_byte_candidates(&block, first_byte) };
+
+            while candidates != 0 {
+                let offset = candidates.trailing_zeros() as usize;
+                candidates &= candidates - 1;
+
+                if block.get(offset..offset + needle.len()) == Some(needle) {
+                    return Ok(Some(self.iter.get_offset() - block.len() + offset));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+}
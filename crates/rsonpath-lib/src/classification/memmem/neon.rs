@@ -0,0 +1,80 @@
+//! `aarch64`/NEON member-label search.
+//!
+//! Like the other SIMD backends, this only vectorizes the prefilter: splat the label's first
+//! byte across a NEON register, compare each incoming block's two halves against it with
+//! `vceqq_u8`, and collapse the result to a [`MaskType`] bitmask of candidate start positions via
+//! [`neon::movemask`]. Candidates are then verified with a plain byte-compare against the full
+//! label, the same as the scalar path.
+use super::*;
+use crate::classification::neon::movemask;
+use crate::input::{Input, InputBlockIterator};
+use crate::query::JsonString;
+use crate::result::InputRecorder;
+use crate::{MaskType, BLOCK_SIZE};
+use core::arch::aarch64::{vceqq_u8, vdupq_n_u8, vld1q_u8};
+
+#[derive(Clone, Copy)]
+pub(crate) struct Constructor;
+
+pub(crate) struct NeonMemmemClassifier<'i, 'b, 'r, I, R>
+where
+    I: Input + 'i,
+    R: InputRecorder<I::Block<'i, BLOCK_SIZE>> + 'r,
+{
+    input: &'i I,
+    iter: &'b mut I::BlockIterator<'i, 'r, BLOCK_SIZE, R>,
+}
+
+impl<'i, 'b, 'r, I, R> NeonMemmemClassifier<'i, 'b, 'r, I, R>
+where
+    I: Input + 'i,
+    R: InputRecorder<I::Block<'i, BLOCK_SIZE>> + 'r,
+{
+    fn new(input: &'i I, iter: &'b mut I::BlockIterator<'i, 'r, BLOCK_SIZE, R>) -> Self {
+        Self { input, iter }
+    }
+
+    /// # Safety
+    /// Requires the `neon` target feature, which is part of the AArch64 baseline.
+    #[inline]
+    unsafe fn half_candidates(half: &[u8], first_byte: u8) -> u16 {
+        let bytes = vld1q_u8(half.as_ptr());
+        movemask(vceqq_u8(bytes, vdupq_n_u8(first_byte)))
+    }
+
+    /// Find the first candidate position in `block` whose byte matches the label's first byte.
+    #[inline]
+    fn first_byte_candidates(block: &[u8], first_byte: u8) -> MaskType {
+        let (half1, half2) = block.split_at(BLOCK_SIZE / 2);
+        // SAFETY: target feature invariant, NEON is part of the AArch64 baseline.
+        unsafe {
+            let lo = Self::half_candidates(half1, first_byte) as MaskType;
+            let hi = Self::half_candidates(half2, first_byte) as MaskType;
+            lo | (hi << (BLOCK_SIZE / 2))
+        }
+    }
+
+    /// Search forward from the current position for the next occurrence of `label`, using the
+    /// vectorized first-byte prefilter to skip blocks that cannot contain a match.
+    pub(crate) fn find_label(&mut self, label: &JsonString) -> Result<Option<usize>, I::Error> {
+        let needle = label.bytes_with_quotes();
+        let Some(&first_byte) = needle.first() else {
+            return Ok(None);
+        };
+
+        while let Some(block) = self.iter.next()? {
+            let mut candidates = Self::first_byte_candidates(&block, first_byte);
+
+            while candidates != 0 {
+                let offset = candidates.trailing_zeros() as usize;
+                candidates &= candidates - 1;
+
+                if block.get(offset..offset + needle.len()) == Some(needle) {
+                    return Ok(Some(self.iter.get_offset() - block.len() + offset));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+}
@@ -0,0 +1,81 @@
+//! Portable member-label search built on `std::simd`.
+//!
+//! Like the `avx2_64`/`sse2_64` classifiers, this only vectorizes the prefilter: the block is
+//! walked in [`CHUNK`]-sized pieces matching a 128-bit vector register, the label's first byte is
+//! splat across a [`Simd<u8, CHUNK>`](core::simd::Simd) lane and compared against each chunk with
+//! [`Simd::simd_eq`], and the per-chunk bitmasks are shifted into place and OR'd into a
+//! [`MaskType`] bitmask of candidate start positions. Candidates are then verified with a plain
+//! byte-compare against the full label, the same as the scalar path -- the win is skipping whole
+//! chunks that contain none of the label's first byte at all, without a per-byte scan to find
+//! that out.
+use super::*;
+use crate::input::{Input, InputBlockIterator};
+use crate::query::JsonString;
+use crate::result::InputRecorder;
+use crate::{MaskType, BLOCK_SIZE};
+use core::simd::{Simd, SimdPartialEq};
+
+/// Width of a single `core::simd` chunk used by this backend, matching a 128-bit vector register.
+const CHUNK: usize = 16;
+
+/// Selects the portable [`std::simd`]-based member-label search.
+#[derive(Clone, Copy)]
+pub(crate) struct Constructor;
+
+pub(crate) struct PortableMemmemClassifier<'i, 'b, 'r, I, R>
+where
+    I: Input + 'i,
+    R: InputRecorder<I::Block<'i, BLOCK_SIZE>> + 'r,
+{
+    input: &'i I,
+    iter: &'b mut I::BlockIterator<'i, 'r, BLOCK_SIZE, R>,
+}
+
+impl<'i, 'b, 'r, I, R> PortableMemmemClassifier<'i, 'b, 'r, I, R>
+where
+    I: Input + 'i,
+    R: InputRecorder<I::Block<'i, BLOCK_SIZE>> + 'r,
+{
+    fn new(input: &'i I, iter: &'b mut I::BlockIterator<'i, 'r, BLOCK_SIZE, R>) -> Self {
+        Self { input, iter }
+    }
+
+    /// Find the first candidate position in `block` whose byte matches the label's first byte.
+    ///
+    /// This is the vectorized prefilter: a false positive (a byte match that isn't actually the
+    /// start of the label) is expected and must still be verified byte-by-byte by the caller.
+    #[inline]
+    fn first_byte_candidates(block: &[u8], first_byte: u8) -> MaskType {
+        let mut candidates: MaskType = 0;
+        for (chunk_idx, chunk) in block.chunks_exact(CHUNK).enumerate() {
+            let bytes: Simd<u8, CHUNK> = Simd::from_slice(chunk);
+            let chunk_candidates = bytes.simd_eq(Simd::splat(first_byte)).to_bitmask() as MaskType;
+            candidates |= chunk_candidates << (chunk_idx * CHUNK);
+        }
+        candidates
+    }
+
+    /// Search forward from the current position for the next occurrence of `label`, using the
+    /// vectorized first-byte prefilter to skip blocks that cannot contain a match.
+    pub(crate) fn find_label(&mut self, label: &JsonString) -> Result<Option<usize>, I::Error> {
+        let needle = label.bytes_with_quotes();
+        let Some(&first_byte) = needle.first() else {
+            return Ok(None);
+        };
+
+        while let Some(block) = self.iter.next()? {
+            let mut candidates = Self::first_byte_candidates(&block, first_byte);
+
+            while candidates != 0 {
+                let offset = candidates.trailing_zeros() as usize;
+                candidates &= candidates - 1;
+
+                if block.get(offset..offset + needle.len()) == Some(needle) {
+                    return Ok(Some(self.iter.get_offset() - block.len() + offset));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+}
@@ -0,0 +1,39 @@
+//! Shared helpers for the `aarch64`/NEON classifier family.
+//!
+//! NEON has no direct counterpart to x86's `pmovmskb`: there is no single instruction that
+//! collapses a `0x00`/`0xFF`-per-lane comparison result into a per-lane bitmask. [`movemask`]
+//! emulates it with the narrowing trick every NEON SIMD JSON parser uses: reinterpret the
+//! `uint8x16_t` result as `uint16x8_t`, shift each 16-bit lane right by 4 and narrow it to 8 bits
+//! with `vshrn_n_u16(v, 4)` into a `uint8x8_t`, then reinterpret that as a single `u64`. Each input
+//! byte survives as a 4-bit nibble in the result, so the real 16-bit-per-block bitmask the
+//! depth/structural/quotes iterators expect is recovered by keeping 1 bit out of every 4.
+#[cfg(target_arch = "aarch64")]
+use core::arch::aarch64::{
+    uint16x8_t, uint8x16_t, uint8x8_t, vget_lane_u64, vreinterpret_u64_u8, vreinterpretq_u16_u8, vshrn_n_u16,
+};
+
+/// Collapse a `uint8x16_t` lane-wise comparison result (each lane `0x00` or `0xFF`) into a
+/// 16-bit mask with one bit per input byte, least-significant bit first.
+///
+/// # Safety
+/// Requires the `neon` target feature, which is part of the AArch64 baseline and thus always
+/// available; the function is still marked `unsafe` to match the calling convention of the
+/// other NEON intrinsics it is built from.
+#[cfg(target_arch = "aarch64")]
+#[inline]
+#[must_use]
+pub(crate) unsafe fn movemask(cmp: uint8x16_t) -> u16 {
+    let widened: uint16x8_t = vreinterpretq_u16_u8(cmp);
+    let narrowed: uint8x8_t = vshrn_n_u16(widened, 4);
+    let nibbles: u64 = vget_lane_u64(vreinterpret_u64_u8(narrowed), 0);
+
+    // Every input byte now occupies a 4-bit nibble in `nibbles`: extract the low bit of each.
+    let mut mask: u16 = 0;
+    for i in 0..16 {
+        let nibble = (nibbles >> (i * 4)) & 0xF;
+        if nibble != 0 {
+            mask |= 1 << i;
+        }
+    }
+    mask
+}
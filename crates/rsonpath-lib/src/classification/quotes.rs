@@ -28,7 +28,7 @@
 //! let block = quote_classifier.next().unwrap();
 //! assert_eq!(expd, block.within_quotes_mask);
 //! ```
-use std::marker::PhantomData;
+use core::marker::PhantomData;
 
 use crate::classification::BLOCK_SIZE;
 use crate::input::{IBlock, Input, InputBlock};
@@ -0,0 +1,30 @@
+use super::{shared::mask_64, *};
+
+super::shared::quotes_classifier!(Constructor, BlockAvx512QuotesClassifier64, 64, u64);
+
+struct BlockAvx512QuotesClassifier64 {
+    internal_classifier: mask_64::BlockClassifier64Bit,
+}
+
+impl BlockAvx512QuotesClassifier64 {
+    fn new() -> Self {
+        Self {
+            internal_classifier: mask_64::BlockClassifier64Bit::new(),
+        }
+    }
+
+    #[inline]
+    fn flip_prev_quote_mask(&mut self) {
+        self.internal_classifier.flip_prev_quote_mask();
+    }
+
+    /// Classify a single 64-byte block in one step, reusing the `pclmulqdq`-backed carry
+    /// propagation from [`mask_64`] -- unlike the narrower SSE2 family there is no wider register
+    /// to fill, so the AVX-512 tier runs the same per-block recurrence as the scalar/SSE2 `u64`
+    /// path, just gated behind the `avx512bw`-accelerated structural/depth classifiers it's
+    /// paired with.
+    #[inline]
+    unsafe fn classify(&mut self, slashes: u64, quotes: u64) -> u64 {
+        self.internal_classifier.classify(slashes, quotes)
+    }
+}
@@ -0,0 +1,128 @@
+//! `aarch64`/NEON quote classification.
+//!
+//! Each half of the block is loaded into a `uint8x16_t` register and compared against a splat
+//! of `"` (and, to find escapes, a splat of `\`) with `vceqq_u8`; [`neon::movemask`] then
+//! collapses each 16-lane comparison into its 16-bit half-mask, and the two halves are combined
+//! into the full [`MaskType`] the same way the SSE2 backend combines its two halves. NEON has no
+//! `pclmulqdq` equivalent widely available without extra target features, so the `"` bitmask is
+//! carried into a within-quotes mask with the same scalar prefix-xor emulation the portable
+//! backend uses.
+use super::*;
+use crate::classification::neon::movemask;
+use crate::classification::{BLOCK_SIZE, MaskType};
+use crate::debug;
+use crate::input::InputBlock;
+use core::arch::aarch64::{vceqq_u8, vdupq_n_u8, vld1q_u8};
+
+/// Bitmask selecting bits on even positions when indexing from zero.
+const ODD: MaskType = 0b0101_0101_0101_0101_0101_0101_0101_0101;
+/// Bitmask selecting bits on odd positions when indexing from zero.
+const EVEN: MaskType = 0b1010_1010_1010_1010_1010_1010_1010_1010;
+
+super::shared::quotes_classifier!(Constructor, BlockNeonClassifier, BLOCK_SIZE, MaskType);
+
+struct BlockNeonClassifier {
+    /// The first bit is lit iff the previous block ended with an unescaped escape character.
+    /// The second bit is lit iff the previous block ended with a starting quote.
+    prev_block_mask: u8,
+}
+
+impl BlockNeonClassifier {
+    fn new() -> Self {
+        Self { prev_block_mask: 0 }
+    }
+
+    #[inline]
+    fn flip_prev_quote_mask(&mut self) {
+        self.prev_block_mask ^= 0x02;
+    }
+
+    #[inline]
+    fn get_prev_slash_mask(&self) -> MaskType {
+        MaskType::from(self.prev_block_mask & 0x01)
+    }
+
+    #[inline]
+    fn get_prev_quote_mask(&self) -> MaskType {
+        MaskType::from((self.prev_block_mask & 0x02) >> 1)
+    }
+
+    #[inline]
+    fn update_prev_block_mask(&mut self, set_slash_mask: bool, quotes: MaskType) {
+        let slash_mask = u8::from(set_slash_mask);
+        let top_bit = MaskType::BITS - 1;
+        let quote_mask = (((quotes & (1 << top_bit)) >> (top_bit - 1)) as u8) & 0x02;
+        self.prev_block_mask = slash_mask | quote_mask;
+    }
+
+    /// Scalar emulation of the `pclmulqdq`-against-all-ones trick: XOR each bit with every bit to
+    /// its right, i.e. a running parity scan, implemented as the classic doubling prefix-xor.
+    #[inline]
+    fn prefix_xor(mut word: MaskType) -> MaskType {
+        let mut shift = 1;
+        while shift < MaskType::BITS {
+            word ^= word << shift;
+            shift *= 2;
+        }
+        word
+    }
+
+    /// Compare one 16-byte half of the block against `needle`, returning its 16-bit lane mask.
+    ///
+    /// # Safety
+    /// Requires the `neon` target feature, which is part of the AArch64 baseline.
+    #[inline]
+    unsafe fn half_mask(half: &[u8], needle: u8) -> u16 {
+        let bytes = vld1q_u8(half.as_ptr());
+        let cmp = vceqq_u8(bytes, vdupq_n_u8(needle));
+        movemask(cmp)
+    }
+
+    #[inline]
+    fn classify<'i, B: InputBlock<'i, BLOCK_SIZE>>(&mut self, block: &B) -> MaskType {
+        let (half1, half2) = block.halves();
+
+        // SAFETY: target feature invariant, NEON is part of the AArch64 baseline.
+        let (quotes, slashes) = unsafe {
+            let quotes_lo = Self::half_mask(half1, b'"') as MaskType;
+            let quotes_hi = Self::half_mask(half2, b'"') as MaskType;
+            let slashes_lo = Self::half_mask(half1, b'\\') as MaskType;
+            let slashes_hi = Self::half_mask(half2, b'\\') as MaskType;
+            (
+                quotes_lo | (quotes_hi << (BLOCK_SIZE / 2)),
+                slashes_lo | (slashes_hi << (BLOCK_SIZE / 2)),
+            )
+        };
+
+        let (escaped, set_prev_slash_mask) = if slashes == 0 {
+            (self.get_prev_slash_mask(), false)
+        } else {
+            let slashes_excluding_escaped_first = slashes & !self.get_prev_slash_mask();
+            let starts = slashes_excluding_escaped_first & !(slashes_excluding_escaped_first << 1);
+            let odd_starts = ODD & starts;
+            let even_starts = EVEN & starts;
+
+            let odd_starts_carry = odd_starts.wrapping_add(slashes);
+            let (even_starts_carry, set_prev_slash_mask) = even_starts.overflowing_add(slashes);
+
+            let ends_of_odd_starts = odd_starts_carry & !slashes;
+            let ends_of_even_starts = even_starts_carry & !slashes;
+
+            let escaped = (ends_of_odd_starts & EVEN) | (ends_of_even_starts & ODD) | self.get_prev_slash_mask();
+
+            (escaped, set_prev_slash_mask)
+        };
+
+        let nonescaped_quotes = (quotes & !escaped) ^ self.get_prev_quote_mask();
+        let within_quotes = Self::prefix_xor(nonescaped_quotes);
+
+        self.update_prev_block_mask(set_prev_slash_mask, within_quotes);
+
+        debug!("quotes:  {:032b}", quotes);
+        debug!("slashes: {:032b}", slashes);
+        debug!("escaped: {:032b}", escaped);
+        debug!("within:  {:032b}", within_quotes);
+
+        within_quotes
+    }
+}
@@ -0,0 +1,133 @@
+//! Portable quote classification built on `std::simd`, used on targets that have no dedicated
+//! intrinsic backend (wasm32, RISC-V, ...).
+//!
+//! The block is walked in [`CHUNK`]-sized pieces matching a 128-bit vector register -- the width
+//! most portable targets (AArch64/NEON, wasm32 SIMD128) actually have -- and [`Simd::simd_eq`]
+//! against a splat of `"` (and, to find escapes, a splat of `\`) produces each chunk's bitmask
+//! with a single portable compare instead of a byte-by-byte scan; the per-chunk bitmasks are then
+//! shifted into place and OR'd into the full block bitmask. There is no portable carryless
+//! multiply to turn the `"` bitmask into a within-quotes mask in one step, so the combined
+//! bitmask is run through the same scalar prefix-xor emulation `slow_quotes` uses on x86 when
+//! `pclmulqdq` is unavailable -- only the bitmask production is vectorized here, not the parity scan.
+use super::*;
+use crate::classification::{BLOCK_SIZE, MaskType};
+use crate::debug;
+use crate::input::InputBlock;
+use core::simd::{Simd, SimdPartialEq};
+
+/// Width of a single `core::simd` chunk used by this backend, matching a 128-bit vector register.
+const CHUNK: usize = 16;
+
+/// Bitmask selecting bits on even positions when indexing from zero.
+const ODD: MaskType = 0b0101_0101_0101_0101_0101_0101_0101_0101;
+/// Bitmask selecting bits on odd positions when indexing from zero.
+const EVEN: MaskType = 0b1010_1010_1010_1010_1010_1010_1010_1010;
+
+/// Selects the portable [`std::simd`]-based classifier.
+super::shared::quotes_classifier!(Constructor, BlockPortableClassifier, BLOCK_SIZE, MaskType);
+
+/// Per-block classifier producing a within-quotes bitmask from raw bytes.
+///
+/// This is the carryless-multiply-free counterpart of the x86 `mask_32`/`mask_64` classifiers:
+/// the escape and quote bitmasks are still produced the same way (an odd/even parity walk over
+/// runs of `\`), but the final "carry the quote parity across the whole block" step, normally a
+/// single `pclmulqdq` against an all-ones vector, is instead a scalar prefix-xor.
+struct BlockPortableClassifier {
+    /// The first bit is lit iff the previous block ended with an unescaped escape character.
+    /// The second bit is lit iff the previous block ended with a starting quote.
+    prev_block_mask: u8,
+}
+
+impl BlockPortableClassifier {
+    fn new() -> Self {
+        Self { prev_block_mask: 0 }
+    }
+
+    #[inline]
+    fn flip_prev_quote_mask(&mut self) {
+        self.prev_block_mask ^= 0x02;
+    }
+
+    #[inline]
+    fn get_prev_slash_mask(&self) -> MaskType {
+        MaskType::from(self.prev_block_mask & 0x01)
+    }
+
+    #[inline]
+    fn get_prev_quote_mask(&self) -> MaskType {
+        MaskType::from((self.prev_block_mask & 0x02) >> 1)
+    }
+
+    #[inline]
+    fn update_prev_block_mask(&mut self, set_slash_mask: bool, quotes: MaskType) {
+        let slash_mask = u8::from(set_slash_mask);
+        let top_bit = MaskType::BITS - 1;
+        let quote_mask = (((quotes & (1 << top_bit)) >> (top_bit - 1)) as u8) & 0x02;
+        self.prev_block_mask = slash_mask | quote_mask;
+    }
+
+    /// Scalar emulation of the `pclmulqdq`-against-all-ones trick: XOR each bit with every bit to
+    /// its right, i.e. a running parity scan, implemented as the classic doubling prefix-xor.
+    #[inline]
+    fn prefix_xor(mut word: MaskType) -> MaskType {
+        let mut shift = 1;
+        while shift < MaskType::BITS {
+            word ^= word << shift;
+            shift *= 2;
+        }
+        word
+    }
+
+    /// Classify one [`CHUNK`]-sized slice of the block, returning `(quotes, slashes)` bitmasks.
+    #[inline]
+    fn classify_chunk(chunk: &[u8]) -> (MaskType, MaskType) {
+        let bytes: Simd<u8, CHUNK> = Simd::from_slice(chunk);
+
+        let quotes = bytes.simd_eq(Simd::splat(b'"')).to_bitmask() as MaskType;
+        let slashes = bytes.simd_eq(Simd::splat(b'\\')).to_bitmask() as MaskType;
+
+        (quotes, slashes)
+    }
+
+    #[inline]
+    fn classify<'i, B: InputBlock<'i, BLOCK_SIZE>>(&mut self, block: &B) -> MaskType {
+        let mut quotes: MaskType = 0;
+        let mut slashes: MaskType = 0;
+        for (chunk_idx, chunk) in block.chunks_exact(CHUNK).enumerate() {
+            let (chunk_quotes, chunk_slashes) = Self::classify_chunk(chunk);
+            quotes |= chunk_quotes << (chunk_idx * CHUNK);
+            slashes |= chunk_slashes << (chunk_idx * CHUNK);
+        }
+
+        let (escaped, set_prev_slash_mask) = if slashes == 0 {
+            (self.get_prev_slash_mask(), false)
+        } else {
+            let slashes_excluding_escaped_first = slashes & !self.get_prev_slash_mask();
+            let starts = slashes_excluding_escaped_first & !(slashes_excluding_escaped_first << 1);
+            let odd_starts = ODD & starts;
+            let even_starts = EVEN & starts;
+
+            let odd_starts_carry = odd_starts.wrapping_add(slashes);
+            let (even_starts_carry, set_prev_slash_mask) = even_starts.overflowing_add(slashes);
+
+            let ends_of_odd_starts = odd_starts_carry & !slashes;
+            let ends_of_even_starts = even_starts_carry & !slashes;
+
+            let escaped = (ends_of_odd_starts & EVEN) | (ends_of_even_starts & ODD) | self.get_prev_slash_mask();
+
+            (escaped, set_prev_slash_mask)
+        };
+
+        let nonescaped_quotes = (quotes & !escaped) ^ self.get_prev_quote_mask();
+        let within_quotes = Self::prefix_xor(nonescaped_quotes);
+
+        self.update_prev_block_mask(set_prev_slash_mask, within_quotes);
+
+        debug!("quotes:  {:032b}", quotes);
+        debug!("slashes: {:032b}", slashes);
+        debug!("escaped: {:032b}", escaped);
+        debug!("within:  {:032b}", within_quotes);
+
+        within_quotes
+    }
+}
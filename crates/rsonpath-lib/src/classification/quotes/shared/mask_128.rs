@@ -0,0 +1,37 @@
+use super::mask_64::BlockClassifier64Bit;
+
+/// Per-128-bit-block quote classifier, built by running the `u64` escape/quote recurrence
+/// from [`super::mask_64::BlockClassifier64Bit`] over each 64-bit half of the block in turn.
+/// The low half is classified first, which naturally carries its trailing escape/quote
+/// parity into the classification of the high half the same way
+/// [`BlockClassifier64Bit`] carries parity from one 64-bit block into the next.
+pub(crate) struct BlockClassifier128Bit {
+    inner: BlockClassifier64Bit,
+}
+
+impl BlockClassifier128Bit {
+    pub(crate) fn new() -> Self {
+        Self {
+            inner: BlockClassifier64Bit::new(),
+        }
+    }
+
+    /// Flip the inter-block state bit representing the quote state.
+    pub(crate) fn flip_prev_quote_mask(&mut self) {
+        self.inner.flip_prev_quote_mask();
+    }
+
+    #[target_feature(enable = "sse2")]
+    #[target_feature(enable = "pclmulqdq")]
+    pub(crate) unsafe fn classify(&mut self, slashes: u128, quotes: u128) -> u128 {
+        let slashes_lo = slashes as u64;
+        let slashes_hi = (slashes >> 64) as u64;
+        let quotes_lo = quotes as u64;
+        let quotes_hi = (quotes >> 64) as u64;
+
+        let within_quotes_lo = self.inner.classify(slashes_lo, quotes_lo);
+        let within_quotes_hi = self.inner.classify(slashes_hi, quotes_hi);
+
+        u128::from(within_quotes_lo) | (u128::from(within_quotes_hi) << 64)
+    }
+}
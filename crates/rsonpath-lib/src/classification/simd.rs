@@ -8,59 +8,66 @@ use super::{
 use crate::{
     input::{Input, InputBlockIterator},
     result::InputRecorder,
-    MaskType, BLOCK_SIZE,
 };
 use cfg_if::cfg_if;
 use log::warn;
-use std::{fmt::Display, marker::PhantomData};
-
-pub trait Simd: Copy {
-    type QuotesClassifier<'i, I>: QuoteClassifiedIterator<'i, I, MaskType, BLOCK_SIZE> + InnerIter<I>
+use core::{fmt::Display, marker::PhantomData};
+
+/// A resolved SIMD backend, generic over the block width `N` and the bitmask type `Mask` used to
+/// represent a block's worth of classification bits (`N` bits, rounded up to `Mask`'s width).
+///
+/// Every dispatchable backend today instantiates this at one of two configurations: the default
+/// `(MaskType, BLOCK_SIZE)` used by the `nosimd`/SSE2/SSSE3/AVX2 family (32-byte blocks, `u32`
+/// masks), or `(u64, 64)` used by the AVX-512 family, which classifies one 64-byte block, a
+/// single AVX-512 register, per step. Keeping `Simd` generic instead of hardcoding the former lets both
+/// configurations coexist behind the same dispatch macro.
+pub trait Simd<Mask, const N: usize>: Copy {
+    type QuotesClassifier<'i, I>: QuoteClassifiedIterator<'i, I, Mask, N> + InnerIter<I>
     where
-        I: InputBlockIterator<'i, BLOCK_SIZE>;
+        I: InputBlockIterator<'i, N>;
 
-    type StructuralClassifier<'i, I>: StructuralIterator<'i, I, Self::QuotesClassifier<'i, I>, MaskType, BLOCK_SIZE>
+    type StructuralClassifier<'i, I>: StructuralIterator<'i, I, Self::QuotesClassifier<'i, I>, Mask, N>
     where
-        I: InputBlockIterator<'i, BLOCK_SIZE>;
+        I: InputBlockIterator<'i, N>;
 
-    type DepthClassifier<'i, I>: DepthIterator<'i, I, Self::QuotesClassifier<'i, I>, MaskType, BLOCK_SIZE>
+    type DepthClassifier<'i, I>: DepthIterator<'i, I, Self::QuotesClassifier<'i, I>, Mask, N>
     where
-        I: InputBlockIterator<'i, BLOCK_SIZE>;
+        I: InputBlockIterator<'i, N>;
 
-    type MemmemClassifier<'i, 'b, 'r, I, R>: Memmem<'i, 'b, 'r, I, BLOCK_SIZE>
+    type MemmemClassifier<'i, 'b, 'r, I, R>: Memmem<'i, 'b, 'r, I, N>
     where
         I: Input + 'i,
-        I::BlockIterator<'i, 'r, BLOCK_SIZE, R>: 'b,
-        R: InputRecorder<I::Block<'i, BLOCK_SIZE>> + 'r,
+        I::BlockIterator<'i, 'r, N, R>: 'b,
+        R: InputRecorder<I::Block<'i, N>> + 'r,
         'i: 'r;
 
     #[must_use]
     fn classify_quoted_sequences<'i, I>(self, iter: I) -> Self::QuotesClassifier<'i, I>
     where
-        I: InputBlockIterator<'i, BLOCK_SIZE>;
+        I: InputBlockIterator<'i, N>;
 
     #[must_use]
     fn resume_quote_classification<'i, I>(
         self,
         iter: I,
         first_block: Option<I::Block>,
-    ) -> ResumedQuoteClassifier<Self::QuotesClassifier<'i, I>, I::Block, MaskType, BLOCK_SIZE>
+    ) -> ResumedQuoteClassifier<Self::QuotesClassifier<'i, I>, I::Block, Mask, N>
     where
-        I: InputBlockIterator<'i, BLOCK_SIZE>;
+        I: InputBlockIterator<'i, N>;
 
     fn classify_structural_characters<'i, I>(
         self,
         iter: Self::QuotesClassifier<'i, I>,
     ) -> Self::StructuralClassifier<'i, I>
     where
-        I: InputBlockIterator<'i, BLOCK_SIZE>;
+        I: InputBlockIterator<'i, N>;
 
     fn resume_structural_classification<'i, I>(
         self,
-        state: ResumeClassifierState<'i, I, Self::QuotesClassifier<'i, I>, MaskType, BLOCK_SIZE>,
+        state: ResumeClassifierState<'i, I, Self::QuotesClassifier<'i, I>, Mask, N>,
     ) -> Self::StructuralClassifier<'i, I>
     where
-        I: InputBlockIterator<'i, BLOCK_SIZE>;
+        I: InputBlockIterator<'i, N>;
 
     fn classify_depth<'i, I>(
         self,
@@ -68,31 +75,24 @@ pub trait Simd: Copy {
         opening: BracketType,
     ) -> Self::DepthClassifier<'i, I>
     where
-        I: InputBlockIterator<'i, BLOCK_SIZE>;
+        I: InputBlockIterator<'i, N>;
 
     fn resume_depth_classification<'i, I>(
         self,
-        state: ResumeClassifierState<'i, I, Self::QuotesClassifier<'i, I>, MaskType, BLOCK_SIZE>,
+        state: ResumeClassifierState<'i, I, Self::QuotesClassifier<'i, I>, Mask, N>,
         opening: BracketType,
-    ) -> DepthIteratorResumeOutcome<
-        'i,
-        I,
-        Self::QuotesClassifier<'i, I>,
-        Self::DepthClassifier<'i, I>,
-        MaskType,
-        BLOCK_SIZE,
-    >
+    ) -> DepthIteratorResumeOutcome<'i, I, Self::QuotesClassifier<'i, I>, Self::DepthClassifier<'i, I>, Mask, N>
     where
-        I: InputBlockIterator<'i, BLOCK_SIZE>;
+        I: InputBlockIterator<'i, N>;
 
     fn memmem<'i, 'b, 'r, I, R>(
         self,
         input: &'i I,
-        iter: &'b mut I::BlockIterator<'i, 'r, BLOCK_SIZE, R>,
+        iter: &'b mut I::BlockIterator<'i, 'r, N, R>,
     ) -> Self::MemmemClassifier<'i, 'b, 'r, I, R>
     where
         I: Input,
-        R: InputRecorder<I::Block<'i, BLOCK_SIZE>>,
+        R: InputRecorder<I::Block<'i, N>>,
         'i: 'r;
 }
 
@@ -114,35 +114,35 @@ impl<Q, S, D, M> ResolvedSimd<Q, S, D, M> {
     }
 }
 
-impl<Q, S, D, M> Simd for ResolvedSimd<Q, S, D, M>
+impl<Mask, const N: usize, Q, S, D, M> Simd<Mask, N> for ResolvedSimd<Q, S, D, M>
 where
-    Q: QuotesImpl,
-    S: StructuralImpl,
-    D: DepthImpl,
-    M: MemmemImpl,
+    Q: QuotesImpl<Mask, N>,
+    S: StructuralImpl<Mask, N>,
+    D: DepthImpl<Mask, N>,
+    M: MemmemImpl<N>,
 {
     type QuotesClassifier<'i, I> = Q::Classifier<'i, I>
     where
-        I: InputBlockIterator<'i, BLOCK_SIZE>;
+        I: InputBlockIterator<'i, N>;
 
     type StructuralClassifier<'i, I> = S::Classifier<'i, I, Self::QuotesClassifier<'i, I>>
     where
-        I: InputBlockIterator<'i, BLOCK_SIZE>;
+        I: InputBlockIterator<'i, N>;
 
     type DepthClassifier<'i, I> = D::Classifier<'i, I, Self::QuotesClassifier<'i, I>>
     where
-        I: InputBlockIterator<'i, BLOCK_SIZE>;
+        I: InputBlockIterator<'i, N>;
 
     type MemmemClassifier<'i, 'b, 'r, I, R> = M::Classifier<'i, 'b, 'r, I, R>
     where
         I: Input + 'i,
-        I::BlockIterator<'i, 'r, BLOCK_SIZE, R>: 'b,
-        R: InputRecorder<I::Block<'i, BLOCK_SIZE>> + 'r,
+        I::BlockIterator<'i, 'r, N, R>: 'b,
+        R: InputRecorder<I::Block<'i, N>> + 'r,
         'i: 'r;
 
     fn classify_quoted_sequences<'i, I>(self, iter: I) -> Self::QuotesClassifier<'i, I>
     where
-        I: InputBlockIterator<'i, BLOCK_SIZE>,
+        I: InputBlockIterator<'i, N>,
     {
         Q::new(iter)
     }
@@ -151,9 +151,9 @@ where
         self,
         iter: I,
         first_block: Option<I::Block>,
-    ) -> ResumedQuoteClassifier<Self::QuotesClassifier<'i, I>, I::Block, MaskType, BLOCK_SIZE>
+    ) -> ResumedQuoteClassifier<Self::QuotesClassifier<'i, I>, I::Block, Mask, N>
     where
-        I: InputBlockIterator<'i, BLOCK_SIZE>,
+        I: InputBlockIterator<'i, N>,
     {
         Q::resume(iter, first_block)
     }
@@ -163,17 +163,17 @@ where
         iter: Self::QuotesClassifier<'i, I>,
     ) -> Self::StructuralClassifier<'i, I>
     where
-        I: InputBlockIterator<'i, BLOCK_SIZE>,
+        I: InputBlockIterator<'i, N>,
     {
         S::new(iter)
     }
 
     fn resume_structural_classification<'i, I>(
         self,
-        state: ResumeClassifierState<'i, I, Self::QuotesClassifier<'i, I>, MaskType, BLOCK_SIZE>,
+        state: ResumeClassifierState<'i, I, Self::QuotesClassifier<'i, I>, Mask, N>,
     ) -> Self::StructuralClassifier<'i, I>
     where
-        I: InputBlockIterator<'i, BLOCK_SIZE>,
+        I: InputBlockIterator<'i, N>,
     {
         S::resume(state)
     }
@@ -184,25 +184,18 @@ where
         opening: BracketType,
     ) -> Self::DepthClassifier<'i, I>
     where
-        I: InputBlockIterator<'i, BLOCK_SIZE>,
+        I: InputBlockIterator<'i, N>,
     {
         D::new(iter, opening)
     }
 
     fn resume_depth_classification<'i, I>(
         self,
-        state: ResumeClassifierState<'i, I, Self::QuotesClassifier<'i, I>, MaskType, BLOCK_SIZE>,
+        state: ResumeClassifierState<'i, I, Self::QuotesClassifier<'i, I>, Mask, N>,
         opening: BracketType,
-    ) -> DepthIteratorResumeOutcome<
-        'i,
-        I,
-        Self::QuotesClassifier<'i, I>,
-        Self::DepthClassifier<'i, I>,
-        MaskType,
-        BLOCK_SIZE,
-    >
+    ) -> DepthIteratorResumeOutcome<'i, I, Self::QuotesClassifier<'i, I>, Self::DepthClassifier<'i, I>, Mask, N>
     where
-        I: InputBlockIterator<'i, BLOCK_SIZE>,
+        I: InputBlockIterator<'i, N>,
     {
         D::resume(state, opening)
     }
@@ -210,11 +203,11 @@ where
     fn memmem<'i, 'b, 'r, I, R>(
         self,
         input: &'i I,
-        iter: &'b mut I::BlockIterator<'i, 'r, BLOCK_SIZE, R>,
+        iter: &'b mut I::BlockIterator<'i, 'r, N, R>,
     ) -> Self::MemmemClassifier<'i, 'b, 'r, I, R>
     where
         I: Input,
-        R: InputRecorder<I::Block<'i, BLOCK_SIZE>>,
+        R: InputRecorder<I::Block<'i, N>>,
         'i: 'r,
     {
         M::memmem(input, iter)
@@ -227,6 +220,16 @@ pub enum SimdTag {
     Sse2,
     Ssse3,
     Avx2,
+    /// The `std::simd`-based backend used on targets without a dedicated intrinsic backend,
+    /// e.g. wasm32 or RISC-V.
+    Portable,
+    /// Classifies 64-byte blocks (one AVX-512 register) with a `u64` mask, implying
+    /// both `fast_quotes` and `fast_popcnt`.
+    Avx512,
+    /// Native `aarch64`/NEON backend. NEON is part of the AArch64 baseline, so unlike the x86
+    /// tiers this is picked unconditionally by `configure` on that target, with no runtime
+    /// feature probe.
+    Neon128,
 }
 
 #[derive(Clone, Copy)]
@@ -248,11 +251,20 @@ impl SimdConfiguration {
     pub(crate) fn fast_popcnt(&self) -> bool {
         self.fast_popcnt
     }
-}
 
-pub const SIMD_OVERRIDE_ENV_VARIABLE: &str = "RSONPATH_UNSAFE_FORCE_SIMD";
+    /// Start building a [`SimdConfiguration`] programmatically, as an alternative to the
+    /// [`RSONPATH_UNSAFE_FORCE_SIMD`](SIMD_OVERRIDE_ENV_VARIABLE) environment variable.
+    ///
+    /// This is the typed counterpart of that override: an embedder that wants to cap the
+    /// backend (e.g. force SSSE3 for a reproducible benchmark, or dodge a CPU-specific bug)
+    /// without shelling out to an env var can assemble a [`SimdConfiguration`] directly and
+    /// have [`SimdConfigurationBuilder::build`] reject it if it violates an invariant the
+    /// [`simd_dispatch`](super::simd::simd_dispatch) macro relies on.
+    #[must_use]
+    pub fn builder() -> SimdConfigurationBuilder {
+        SimdConfigurationBuilder::new()
+    }
 
-impl SimdConfiguration {
     fn try_parse(str: &str) -> Option<Self> {
         let parts = str.split(';').collect::<Vec<_>>();
 
@@ -264,13 +276,7 @@ impl SimdConfiguration {
         let quotes_str = parts[1];
         let popcnt_str = parts[2];
 
-        let simd = match simd_slug.to_ascii_lowercase().as_ref() {
-            "nosimd" => Some(SimdTag::Nosimd),
-            "sse2+" => Some(SimdTag::Sse2),
-            "ssse3+" => Some(SimdTag::Ssse3),
-            "avx2+" => Some(SimdTag::Avx2),
-            _ => None,
-        };
+        let simd = SimdTag::from_slug(simd_slug);
         let quotes = match quotes_str.to_ascii_lowercase().as_ref() {
             "fast_quotes" => Some(true),
             "slow_quotes" => Some(false),
@@ -282,14 +288,196 @@ impl SimdConfiguration {
             _ => None,
         };
 
-        Some(Self {
-            highest_simd: simd?,
-            fast_quotes: quotes?,
-            fast_popcnt: popcnt?,
+        let mut builder = SimdConfigurationBuilder::new().highest_simd(simd?);
+        builder = builder.fast_quotes(quotes?).fast_popcnt(popcnt?);
+        builder.build().ok()
+    }
+}
+
+pub const SIMD_OVERRIDE_ENV_VARIABLE: &str = "RSONPATH_UNSAFE_FORCE_SIMD";
+
+/// Builder for a [`SimdConfiguration`], mirroring the `File::options()` style of options
+/// builders used elsewhere in the standard library.
+///
+/// Unset `fast_quotes`/`fast_popcnt` default to `false`; [`build`](Self::build) is the single
+/// point where the invariants the dispatch macro assumes (AVX2 and AVX-512 both imply fast
+/// quote classification and fast popcount) are checked, returning a [`SimdConfigurationError`]
+/// instead of panicking if they're violated.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SimdConfigurationBuilder {
+    highest_simd: Option<SimdTag>,
+    fast_quotes: bool,
+    fast_popcnt: bool,
+}
+
+impl SimdConfigurationBuilder {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the highest SIMD backend to dispatch to.
+    #[must_use]
+    pub fn highest_simd(mut self, highest_simd: SimdTag) -> Self {
+        self.highest_simd = Some(highest_simd);
+        self
+    }
+
+    /// Set whether quote classification should use the fast, `pclmulqdq`-based carry propagation.
+    #[must_use]
+    pub fn fast_quotes(mut self, fast_quotes: bool) -> Self {
+        self.fast_quotes = fast_quotes;
+        self
+    }
+
+    /// Set whether depth classification should use a hardware `POPCNT` instruction.
+    #[must_use]
+    pub fn fast_popcnt(mut self, fast_popcnt: bool) -> Self {
+        self.fast_popcnt = fast_popcnt;
+        self
+    }
+
+    /// Validate and build the [`SimdConfiguration`].
+    ///
+    /// # Errors
+    /// Returns an error if [`highest_simd`](Self::highest_simd) was never called, or if the
+    /// chosen backend requires `fast_quotes`/`fast_popcnt` that were not both enabled.
+    pub fn build(self) -> Result<SimdConfiguration, SimdConfigurationError> {
+        let highest_simd = self.highest_simd.ok_or(SimdConfigurationError::MissingHighestSimd)?;
+
+        if matches!(highest_simd, SimdTag::Avx2 | SimdTag::Avx512) && !(self.fast_quotes && self.fast_popcnt) {
+            return Err(SimdConfigurationError::FastFlagsRequired(highest_simd));
+        }
+
+        Ok(SimdConfiguration {
+            highest_simd,
+            fast_quotes: self.fast_quotes,
+            fast_popcnt: self.fast_popcnt,
         })
     }
 }
 
+/// Errors that can occur while building a [`SimdConfiguration`] via [`SimdConfigurationBuilder`].
+#[derive(Debug, thiserror::Error)]
+pub enum SimdConfigurationError {
+    /// [`SimdConfigurationBuilder::highest_simd`] was never called.
+    #[error("no highest_simd was set on the SimdConfiguration builder")]
+    MissingHighestSimd,
+    /// The chosen backend requires both `fast_quotes` and `fast_popcnt`, but at least one was unset.
+    #[error("{0} requires both fast_quotes and fast_popcnt to be enabled")]
+    FastFlagsRequired(SimdTag),
+    /// A [`SimdTag`] was requested that the current target/CPU cannot actually run.
+    #[error("{0} is not supported on this target")]
+    UnsupportedOnTarget(SimdTag),
+    /// The [`SIMD_TAG_OVERRIDE_ENV_VARIABLE`] was set to a string that isn't a known [`SimdTag`] slug.
+    #[error("'{0}' set via {SIMD_TAG_OVERRIDE_ENV_VARIABLE} is not a recognized SIMD tag")]
+    UnknownTag(String),
+}
+
+impl SimdTag {
+    fn slug(self) -> &'static str {
+        match self {
+            SimdTag::Nosimd => "nosimd",
+            SimdTag::Sse2 => "sse2+",
+            SimdTag::Ssse3 => "ssse3+",
+            SimdTag::Avx2 => "avx2+",
+            SimdTag::Avx512 => "avx512+",
+            SimdTag::Neon128 => "neon128+",
+            SimdTag::Portable => "portable",
+        }
+    }
+
+    fn from_slug(slug: &str) -> Option<Self> {
+        match slug.to_ascii_lowercase().as_ref() {
+            "nosimd" => Some(SimdTag::Nosimd),
+            "sse2+" => Some(SimdTag::Sse2),
+            "ssse3+" => Some(SimdTag::Ssse3),
+            "avx2+" => Some(SimdTag::Avx2),
+            "avx512+" => Some(SimdTag::Avx512),
+            "neon128+" => Some(SimdTag::Neon128),
+            "portable" => Some(SimdTag::Portable),
+            _ => None,
+        }
+    }
+
+    /// Whether this tag's backend can actually run on the CPU executing this code right now.
+    ///
+    /// This checks both that the target architecture has an implementation for the tag at all
+    /// (e.g. [`SimdTag::Neon128`] only exists on `aarch64`) and, for the x86(_64) tiers, that the
+    /// CPU feature each tier needs is actually present -- the same probes [`configure`] uses to
+    /// pick a default, just checked against a caller-chosen tag instead.
+    #[must_use]
+    pub fn is_supported_on_current_target(self) -> bool {
+        cfg_if! {
+            if #[cfg(any(target_arch = "x86", target_arch = "x86_64"))] {
+                match self {
+                    SimdTag::Nosimd => true,
+                    SimdTag::Sse2 => is_x86_feature_detected!("sse2"),
+                    SimdTag::Ssse3 => is_x86_feature_detected!("ssse3"),
+                    SimdTag::Avx2 => is_x86_feature_detected!("avx2"),
+                    SimdTag::Avx512 => is_x86_feature_detected!("avx512bw"),
+                    SimdTag::Portable => true,
+                    SimdTag::Neon128 => false,
+                }
+            } else if #[cfg(target_arch = "aarch64")] {
+                match self {
+                    SimdTag::Neon128 | SimdTag::Portable => true,
+                    SimdTag::Nosimd | SimdTag::Sse2 | SimdTag::Ssse3 | SimdTag::Avx2 | SimdTag::Avx512 => false,
+                }
+            } else {
+                matches!(self, SimdTag::Portable)
+            }
+        }
+    }
+
+    /// Picks the highest tier the current CPU actually supports, via the same runtime
+    /// feature probes [`is_supported_on_current_target`](Self::is_supported_on_current_target)
+    /// checks a caller-chosen tag against -- this just picks the best one outright instead.
+    ///
+    /// Does not consult the [`SIMD_OVERRIDE_ENV_VARIABLE`] escape hatch; [`configure`] layers
+    /// that override on top of this.
+    #[must_use]
+    pub(crate) fn detect_highest() -> Self {
+        cfg_if! {
+            if #[cfg(any(target_arch = "x86", target_arch = "x86_64"))] {
+                if is_x86_feature_detected!("avx512bw") {
+                    Self::Avx512
+                } else if is_x86_feature_detected!("avx2") {
+                    Self::Avx2
+                } else if is_x86_feature_detected!("ssse3") {
+                    Self::Ssse3
+                } else if is_x86_feature_detected!("sse2") {
+                    Self::Sse2
+                } else {
+                    Self::Nosimd
+                }
+            } else if #[cfg(target_arch = "aarch64")] {
+                // NEON is part of the AArch64 baseline (unlike x86's feature tiers), so there is
+                // no runtime probe to run: every AArch64 target can use the native backend.
+                Self::Neon128
+            } else {
+                // No CPU feature string to probe and no native backend for this target; the
+                // portable `std::simd` backend is always available and is the best we can pick
+                // without arch-specific intrinsics (e.g. wasm32, RISC-V).
+                Self::Portable
+            }
+        }
+    }
+}
+
+impl Display for SimdTag {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.slug())
+    }
+}
+
+/// Auto-detects the best [`SimdConfiguration`] for the current CPU, honoring the
+/// [`RSONPATH_UNSAFE_FORCE_SIMD`](SIMD_OVERRIDE_ENV_VARIABLE) environment variable override.
+///
+/// Requires the `std` feature: reading the process environment is not available under `no_std`.
+/// The const-generic classifier machinery this selects between has no such requirement; only the
+/// auto-detection entry points do.
+#[cfg(feature = "std")]
 #[inline]
 #[must_use]
 pub fn configure() -> SimdConfiguration {
@@ -302,39 +490,104 @@ pub fn configure() -> SimdConfiguration {
         return SimdConfiguration::try_parse(&simd).expect("invalid simd configuration override");
     }
 
-    let highest_simd = if is_x86_feature_detected!("avx2") {
-        SimdTag::Avx2
-    } else if is_x86_feature_detected!("ssse3") {
-        SimdTag::Ssse3
-    } else if is_x86_feature_detected!("sse2") {
-        SimdTag::Sse2
-    } else {
-        SimdTag::Nosimd
-    };
+    let highest_simd = SimdTag::detect_highest();
+
+    cfg_if! {
+        if #[cfg(any(target_arch = "x86", target_arch = "x86_64"))] {
+            let fast_quotes = is_x86_feature_detected!("pclmulqdq");
+            let fast_popcnt = is_x86_feature_detected!("popcnt");
+
+            SimdConfiguration {
+                highest_simd,
+                fast_quotes,
+                fast_popcnt,
+            }
+        } else if #[cfg(target_arch = "aarch64")] {
+            SimdConfiguration {
+                highest_simd,
+                fast_quotes: false,
+                fast_popcnt: false,
+            }
+        } else {
+            SimdConfiguration {
+                highest_simd,
+                fast_quotes: false,
+                fast_popcnt: false,
+            }
+        }
+    }
+}
 
-    let fast_quotes = is_x86_feature_detected!("pclmulqdq");
-    let fast_popcnt = is_x86_feature_detected!("popcnt");
+/// Name of the environment variable read by [`configure_with_override`] to pin dispatch to a
+/// specific [`SimdTag`] for benchmarking backends head-to-head or reproducing a bug on hardware
+/// that would otherwise auto-select a different path.
+///
+/// Unlike [`SIMD_OVERRIDE_ENV_VARIABLE`], this only names a tag (e.g. `RSONPATH_SIMD=ssse3+`):
+/// `fast_quotes`/`fast_popcnt` are still probed from the real CPU, and the requested tag is
+/// validated against [`SimdTag::is_supported_on_current_target`] rather than trusted blindly.
+#[cfg(feature = "std")]
+pub const SIMD_TAG_OVERRIDE_ENV_VARIABLE: &str = "RSONPATH_SIMD";
+
+/// Resolve a forced [`SimdTag`] into a full, validated [`SimdConfiguration`], using this CPU's
+/// real feature detection for `fast_quotes`/`fast_popcnt` rather than assuming the best case.
+///
+/// Requires the `std` feature; see [`configure`].
+///
+/// # Errors
+/// Returns [`SimdConfigurationError::UnsupportedOnTarget`] if `tag` cannot run on the current
+/// target/CPU, or [`SimdConfigurationError::FastFlagsRequired`] if it can, but this CPU lacks a
+/// feature ([`SimdTag::Avx2`]/[`SimdTag::Avx512`] always imply `pclmulqdq` and `popcnt`) that the
+/// requested tag's dispatch arm assumes.
+#[cfg(feature = "std")]
+pub fn configure_forced(tag: SimdTag) -> Result<SimdConfiguration, SimdConfigurationError> {
+    if !tag.is_supported_on_current_target() {
+        return Err(SimdConfigurationError::UnsupportedOnTarget(tag));
+    }
+
+    let detected = configure();
+    SimdConfiguration::builder()
+        .highest_simd(tag)
+        .fast_quotes(detected.fast_quotes)
+        .fast_popcnt(detected.fast_popcnt)
+        .build()
+}
 
-    SimdConfiguration {
-        highest_simd,
-        fast_quotes,
-        fast_popcnt,
+/// Resolve the [`SimdConfiguration`] to use, honoring [`SIMD_TAG_OVERRIDE_ENV_VARIABLE`] if set,
+/// falling back to [`configure`]'s CPU auto-detection otherwise.
+///
+/// The env var is parsed and validated once per process and cached, since it cannot change
+/// between calls within a run.
+///
+/// Requires the `std` feature; see [`configure`].
+///
+/// # Errors
+/// Returns [`SimdConfigurationError::UnknownTag`] if the env var is set to an unrecognized slug,
+/// or any error [`configure_forced`] can return if it names a tag unsupported on this target/CPU.
+#[cfg(feature = "std")]
+pub fn configure_with_override() -> Result<SimdConfiguration, SimdConfigurationError> {
+    use std::sync::OnceLock;
+
+    static FORCED_TAG: OnceLock<Result<Option<SimdTag>, String>> = OnceLock::new();
+
+    let forced = FORCED_TAG.get_or_init(|| match std::env::var(SIMD_TAG_OVERRIDE_ENV_VARIABLE) {
+        Ok(slug) => SimdTag::from_slug(&slug).map(Some).ok_or(slug),
+        Err(_) => Ok(None),
+    });
+
+    match forced {
+        Ok(Some(tag)) => configure_forced(*tag),
+        Ok(None) => Ok(configure()),
+        Err(slug) => Err(SimdConfigurationError::UnknownTag(slug.clone())),
     }
 }
 
 impl Display for SimdConfiguration {
     #[inline]
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let simd_slug = match self.highest_simd {
-            SimdTag::Nosimd => "nosimd",
-            SimdTag::Sse2 => "sse2+",
-            SimdTag::Ssse3 => "ssse3+",
-            SimdTag::Avx2 => "avx2+",
-        };
         let quote_desc = if self.fast_quotes { "fast_quotes" } else { "slow_quotes" };
         let popcnt_desc = if self.fast_popcnt { "fast_popcnt" } else { "slow_popcnt" };
 
-        write!(f, "{simd_slug};{quote_desc};{popcnt_desc}")
+        write!(f, "{};{quote_desc};{popcnt_desc}", self.highest_simd)
     }
 }
 
@@ -346,6 +599,18 @@ cfg_if! {
                     let conf = $conf;
 
                     match conf.highest_simd() {
+                        // AVX-512 implies all other optimizations, and doubles the block width.
+                        $crate::classification::simd::SimdTag::Avx512 => {
+                            assert!(conf.fast_quotes());
+                            assert!(conf.fast_popcnt());
+                            let $simd = $crate::classification::simd::ResolvedSimd::<
+                                $crate::classification::quotes::avx512_64::Constructor,
+                                $crate::classification::structural::avx512_64::Constructor,
+                                $crate::classification::depth::avx512_64::Constructor,
+                                $crate::classification::memmem::avx512_64::Constructor,
+                            >::new();
+                            $b
+                        }
                         // AVX2 implies all other optimizations.
                         $crate::classification::simd::SimdTag::Avx2 => {
                             assert!(conf.fast_quotes());
@@ -451,6 +716,27 @@ cfg_if! {
                             >::new();
                             $b
                         }
+                        // Only reachable via an explicit override; `configure` never picks this on x86.
+                        $crate::classification::simd::SimdTag::Portable => {
+                            let $simd = $crate::classification::simd::ResolvedSimd::<
+                                $crate::classification::quotes::portable::Constructor,
+                                $crate::classification::structural::portable::Constructor,
+                                $crate::classification::depth::portable::Constructor,
+                                $crate::classification::memmem::portable::Constructor,
+                            >::new();
+                            $b
+                        }
+                        // NEON is not available on x86(_64); only reachable via an explicit
+                        // override, in which case we fall back to the scalar path.
+                        $crate::classification::simd::SimdTag::Neon128 => {
+                            let $simd = $crate::classification::simd::ResolvedSimd::<
+                                $crate::classification::quotes::nosimd::Constructor,
+                                $crate::classification::structural::nosimd::Constructor,
+                                $crate::classification::depth::nosimd::Constructor,
+                                $crate::classification::memmem::nosimd::Constructor,
+                            >::new();
+                            $b
+                        }
                     }
                 }
             };
@@ -463,6 +749,18 @@ cfg_if! {
                     let conf = $conf;
 
                     match conf.highest_simd() {
+                        // AVX-512 is not available on 32-bit x86; treat it the same as AVX2.
+                        $crate::classification::simd::SimdTag::Avx512 => {
+                            assert!(conf.fast_quotes());
+                            assert!(conf.fast_popcnt());
+                            let $simd = $crate::classification::simd::ResolvedSimd::<
+                                $crate::classification::quotes::avx2_32::Constructor,
+                                $crate::classification::structural::avx2_32::Constructor,
+                                $crate::classification::depth::avx2_32::Constructor,
+                                $crate::classification::memmem::avx2_32::Constructor,
+                            >::new();
+                            $b
+                        }
                         // AVX2 implies all other optimizations.
                         $crate::classification::simd::SimdTag::Avx2 => {
                             assert!(conf.fast_quotes());
@@ -568,24 +866,110 @@ cfg_if! {
                             >::new();
                             $b
                         }
+                        // Only reachable via an explicit override; `configure` never picks this on x86.
+                        $crate::classification::simd::SimdTag::Portable => {
+                            let $simd = $crate::classification::simd::ResolvedSimd::<
+                                $crate::classification::quotes::portable::Constructor,
+                                $crate::classification::structural::portable::Constructor,
+                                $crate::classification::depth::portable::Constructor,
+                                $crate::classification::memmem::portable::Constructor,
+                            >::new();
+                            $b
+                        }
+                        // NEON is not available on x86(_64); only reachable via an explicit
+                        // override, in which case we fall back to the scalar path.
+                        $crate::classification::simd::SimdTag::Neon128 => {
+                            let $simd = $crate::classification::simd::ResolvedSimd::<
+                                $crate::classification::quotes::nosimd::Constructor,
+                                $crate::classification::structural::nosimd::Constructor,
+                                $crate::classification::depth::nosimd::Constructor,
+                                $crate::classification::memmem::nosimd::Constructor,
+                            >::new();
+                            $b
+                        }
+                    }
+                }
+            };
+        }
+    }
+    else if #[cfg(target_arch = "aarch64")] {
+        macro_rules! simd_dispatch {
+            ($conf:expr => |$simd:ident| $b:block) => {
+                {
+                    let conf = $conf;
+
+                    match conf.highest_simd() {
+                        $crate::classification::simd::SimdTag::Neon128 => {
+                            let $simd = $crate::classification::simd::ResolvedSimd::<
+                                $crate::classification::quotes::neon::Constructor,
+                                $crate::classification::structural::neon::Constructor,
+                                $crate::classification::depth::neon::Constructor,
+                                $crate::classification::memmem::neon::Constructor,
+                            >::new();
+                            $b
+                        }
+                        // Only reachable via an explicit override; `configure` never picks this
+                        // on AArch64.
+                        $crate::classification::simd::SimdTag::Portable => {
+                            let $simd = $crate::classification::simd::ResolvedSimd::<
+                                $crate::classification::quotes::portable::Constructor,
+                                $crate::classification::structural::portable::Constructor,
+                                $crate::classification::depth::portable::Constructor,
+                                $crate::classification::memmem::portable::Constructor,
+                            >::new();
+                            $b
+                        }
+                        // x86-only tiers are not available on AArch64; only reachable via an
+                        // explicit override, in which case we fall back to the native backend.
+                        $crate::classification::simd::SimdTag::Nosimd
+                        | $crate::classification::simd::SimdTag::Sse2
+                        | $crate::classification::simd::SimdTag::Ssse3
+                        | $crate::classification::simd::SimdTag::Avx2
+                        | $crate::classification::simd::SimdTag::Avx512 => {
+                            let $simd = $crate::classification::simd::ResolvedSimd::<
+                                $crate::classification::quotes::neon::Constructor,
+                                $crate::classification::structural::neon::Constructor,
+                                $crate::classification::depth::neon::Constructor,
+                                $crate::classification::memmem::neon::Constructor,
+                            >::new();
+                            $b
+                        }
                     }
                 }
             };
         }
     }
     else {
+        // No dedicated intrinsic backend on this target (e.g. wasm32, RISC-V): the
+        // configuration always resolves to `SimdTag::Portable`, so there's nothing to match on.
         macro_rules! simd_dispatch {
             ($conf:expr => |$simd:ident| $b:block) => {
-                let $simd = $crate::classification::simd::ResolvedSimd::<
-                    $crate::classification::quotes::nosimd::Constructor,
-                    $crate::classification::structural::nosimd::Constructor,
-                    $crate::classification::depth::nosimd::Constructor,
-                    $crate::classification::memmem::nosimd::Constructor,
-                >::new();
-                $b
+                {
+                    let conf = $conf;
+                    assert!(matches!(conf.highest_simd(), $crate::classification::simd::SimdTag::Portable));
+
+                    let $simd = $crate::classification::simd::ResolvedSimd::<
+                        $crate::classification::quotes::portable::Constructor,
+                        $crate::classification::structural::portable::Constructor,
+                        $crate::classification::depth::portable::Constructor,
+                        $crate::classification::memmem::portable::Constructor,
+                    >::new();
+                    $b
+                }
             };
         }
     }
 }
 
 pub(crate) use simd_dispatch;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_highest_picks_a_tag_the_current_cpu_actually_supports() {
+        let detected = SimdTag::detect_highest();
+        assert!(detected.is_supported_on_current_target());
+    }
+}
@@ -0,0 +1,68 @@
+//! `aarch64`/NEON structural classification.
+//!
+//! Each half of the block is compared against a splat of each structural delimiter
+//! (`{ } [ ] : ,`) with `vceqq_u8`, the six per-delimiter comparisons are OR'd together with
+//! `vorrq_u8`, and [`neon::movemask`] collapses the combined 16-lane result into a half-mask;
+//! the two halves are combined into the full [`MaskType`] the same way the SSE2 backend does.
+use super::{shared::mask_32, *};
+use crate::classification::neon::movemask;
+use crate::classification::{QuoteClassifiedBlock, ResumeClassifierBlockState};
+use crate::debug;
+use crate::input::InputBlock;
+use crate::{MaskType, BLOCK_SIZE};
+use core::arch::aarch64::{vceqq_u8, vdupq_n_u8, vld1q_u8, vorrq_u8};
+
+super::shared::structural_classifier!(Constructor, BlockNeonClassifier, mask_32, BLOCK_SIZE, MaskType);
+
+struct BlockNeonClassifier;
+
+impl BlockNeonClassifier {
+    fn new() -> Self {
+        Self
+    }
+
+    /// Compare one 16-byte half of the block against the structural character set, returning
+    /// its 16-bit lane mask.
+    ///
+    /// # Safety
+    /// Requires the `neon` target feature, which is part of the AArch64 baseline.
+    #[inline]
+    unsafe fn half_mask(half: &[u8]) -> u16 {
+        let bytes = vld1q_u8(half.as_ptr());
+
+        let opening_braces = vceqq_u8(bytes, vdupq_n_u8(b'{'));
+        let closing_braces = vceqq_u8(bytes, vdupq_n_u8(b'}'));
+        let opening_brackets = vceqq_u8(bytes, vdupq_n_u8(b'['));
+        let closing_brackets = vceqq_u8(bytes, vdupq_n_u8(b']'));
+        let colons = vceqq_u8(bytes, vdupq_n_u8(b':'));
+        let commas = vceqq_u8(bytes, vdupq_n_u8(b','));
+
+        let structural = vorrq_u8(
+            vorrq_u8(vorrq_u8(opening_braces, closing_braces), vorrq_u8(opening_brackets, closing_brackets)),
+            vorrq_u8(colons, commas),
+        );
+
+        movemask(structural)
+    }
+
+    #[inline]
+    fn classify<'i, B: InputBlock<'i, BLOCK_SIZE>>(
+        &mut self,
+        quote_classified_block: QuoteClassifiedBlock<B, MaskType, BLOCK_SIZE>,
+    ) -> mask_32::StructuralsBlock<B> {
+        let (half1, half2) = quote_classified_block.block.halves();
+
+        // SAFETY: target feature invariant, NEON is part of the AArch64 baseline.
+        let structural = unsafe {
+            let lo = Self::half_mask(half1) as MaskType;
+            let hi = Self::half_mask(half2) as MaskType;
+            lo | (hi << (BLOCK_SIZE / 2))
+        };
+        let nonquoted_structural = structural & !quote_classified_block.within_quotes_mask;
+
+        debug!("structural:           {:032b}", structural);
+        debug!("nonquoted_structural: {:032b}", nonquoted_structural);
+
+        mask_32::StructuralsBlock::new(quote_classified_block, nonquoted_structural)
+    }
+}
@@ -0,0 +1,69 @@
+//! Portable structural classification built on `std::simd`.
+//!
+//! Rather than loading a whole block into one [`Simd<u8, BLOCK_SIZE>`](core::simd::Simd) vector,
+//! the block is walked in [`CHUNK`]-sized pieces matching the 128-bit registers most portable
+//! targets (AArch64 NEON, wasm32 SIMD128) actually have: a `Simd<u8, BLOCK_SIZE>` compare would
+//! either be scalarized or stitched together from several real vector ops on those targets, so
+//! chunking by hand keeps each [`Simd::simd_eq`] a single native instruction. Membership in the
+//! structural character set `{ } [ ] : ,` is computed per chunk, with the six per-delimiter
+//! [`Mask`](core::simd::Mask)es OR'd together and collapsed to a [`CHUNK`]-bit mask via
+//! [`Mask::to_bitmask`], which is then shifted into its place in the full block bitmask. This is
+//! the same shape of computation the `avx512_64`/`avx2_64` classifiers run with intrinsics, just
+//! expressed against the portable vector API.
+use super::{shared::mask_32, *};
+use crate::classification::{QuoteClassifiedBlock, ResumeClassifierBlockState};
+use crate::input::InputBlock;
+use crate::{debug, MaskType, BLOCK_SIZE};
+use core::simd::{Simd, SimdPartialEq};
+
+super::shared::structural_classifier!(Constructor, BlockPortableClassifier, mask_32, BLOCK_SIZE, MaskType);
+
+/// Width of a single `core::simd` chunk used by this backend, matching a 128-bit vector register.
+const CHUNK: usize = 16;
+
+struct BlockPortableClassifier;
+
+impl BlockPortableClassifier {
+    fn new() -> Self {
+        Self
+    }
+
+    /// Classify one [`CHUNK`]-sized slice of the block, returning a `CHUNK`-bit membership mask.
+    #[inline]
+    fn classify_chunk(chunk: &[u8]) -> MaskType {
+        let bytes: Simd<u8, CHUNK> = Simd::from_slice(chunk);
+
+        let opening_braces = bytes.simd_eq(Simd::splat(b'{'));
+        let closing_braces = bytes.simd_eq(Simd::splat(b'}'));
+        let opening_brackets = bytes.simd_eq(Simd::splat(b'['));
+        let closing_brackets = bytes.simd_eq(Simd::splat(b']'));
+        let colons = bytes.simd_eq(Simd::splat(b':'));
+        let commas = bytes.simd_eq(Simd::splat(b','));
+
+        let structural_mask = opening_braces
+            | closing_braces
+            | opening_brackets
+            | closing_brackets
+            | colons
+            | commas;
+
+        structural_mask.to_bitmask() as MaskType
+    }
+
+    #[inline]
+    fn classify<'i, B: InputBlock<'i, BLOCK_SIZE>>(
+        &mut self,
+        quote_classified_block: QuoteClassifiedBlock<B, MaskType, BLOCK_SIZE>,
+    ) -> mask_32::StructuralsBlock<B> {
+        let mut structural: MaskType = 0;
+        for (chunk_idx, chunk) in quote_classified_block.block.chunks_exact(CHUNK).enumerate() {
+            structural |= Self::classify_chunk(chunk) << (chunk_idx * CHUNK);
+        }
+        let nonquoted_structural = structural & !quote_classified_block.within_quotes_mask;
+
+        debug!("structural:           {:032b}", structural);
+        debug!("nonquoted_structural: {:032b}", nonquoted_structural);
+
+        mask_32::StructuralsBlock::new(quote_classified_block, nonquoted_structural)
+    }
+}
@@ -0,0 +1,24 @@
+use crate::classification::QuoteClassifiedBlock;
+use crate::input::InputBlock;
+
+/// A block of input together with the bitmask of its structural characters (`{ } [ ] : ,`)
+/// that are not within a quoted sequence, as produced by a 128-byte-block structural classifier.
+///
+/// Mirrors the smaller `mask_32`/`mask_64` variants used by the narrower backends, just widened
+/// to a 128-byte block with a `u128` mask.
+pub(crate) struct StructuralsBlock<B> {
+    /// The quote-classified block this structural mask was computed from.
+    pub(crate) quote_classified: QuoteClassifiedBlock<B, u128, 128>,
+    /// Mask of structural characters in [`Self::quote_classified`] that are not within quotes.
+    pub(crate) nonquoted_structural: u128,
+}
+
+impl<'i, B: InputBlock<'i, 128>> StructuralsBlock<B> {
+    /// Pair a quote-classified block with the nonquoted-structural mask computed for it.
+    pub(crate) fn new(quote_classified: QuoteClassifiedBlock<B, u128, 128>, nonquoted_structural: u128) -> Self {
+        Self {
+            quote_classified,
+            nonquoted_structural,
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use crate::classification::QuoteClassifiedBlock;
+use crate::input::InputBlock;
+
+/// A block of input together with the bitmask of its structural characters (`{ } [ ] : ,`)
+/// that are not within a quoted sequence, as produced by a 64-byte-block structural classifier.
+///
+/// Mirrors [`super::mask_128::StructuralsBlock`], just narrowed to the single-register 64-byte
+/// block size the AVX-512 classifier operates on when it classifies one block per step instead
+/// of two concatenated halves.
+pub(crate) struct StructuralsBlock<B> {
+    /// The quote-classified block this structural mask was computed from.
+    pub(crate) quote_classified: QuoteClassifiedBlock<B, u64, 64>,
+    /// Mask of structural characters in [`Self::quote_classified`] that are not within quotes.
+    pub(crate) nonquoted_structural: u64,
+}
+
+impl<'i, B: InputBlock<'i, 64>> StructuralsBlock<B> {
+    /// Pair a quote-classified block with the nonquoted-structural mask computed for it.
+    pub(crate) fn new(quote_classified: QuoteClassifiedBlock<B, u64, 64>, nonquoted_structural: u64) -> Self {
+        Self {
+            quote_classified,
+            nonquoted_structural,
+        }
+    }
+}
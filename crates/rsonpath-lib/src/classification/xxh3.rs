@@ -0,0 +1,233 @@
+//! Fast rejection of non-matching member labels via an XXH3-style hash.
+//!
+//! Comparing a candidate JSON object key against a target label byte-by-byte
+//! costs O(len) even on a miss, which adds up for objects with many distinct
+//! keys. [`LabelHash`] precomputes a 64-bit hash of the target label at
+//! query-compile time; at execution time, once the quote classifier has
+//! delimited a candidate key's bytes, [`LabelHash::matches`] hashes those bytes
+//! and compares against the stored hash first, only falling through to an
+//! exact byte comparison on a hash match.
+//!
+//! The hash itself follows XXH3-64's overall shape: input longer than 16 bytes
+//! is processed in 16-byte lanes, each XOR-folded against a fixed secret and
+//! mixed as a 64x64-to-128-bit multiplication whose two halves are folded back
+//! together and accumulated, with a final avalanche (xor-shift, multiply,
+//! xor-shift, multiply, xor-shift) mixing the accumulator's bits before it's returned.
+//! Inputs of 16 bytes or fewer — the overwhelming common case for JSON object
+//! keys — take a dedicated short path that never touches lane accumulation.
+//!
+//! This module is declared for real now (`classification.rs` has a `pub(crate) mod
+//! xxh3;`, reachable from the crate root via `lib.rs`), so "no engine touches it" is no
+//! longer about reachability. The engine itself is the remaining gap: `crates/rsonpath/
+//! src/main.rs` imports `rsonpath_lib::engine::{main::MainEngine, recursive::
+//! RecursiveEngine, Compiler, Engine}`, but no `engine` module exists anywhere under
+//! `rsonpath-lib/src` for this crate to define -- the query executor the request wants
+//! running this hash during execution isn't merely unwired, it's entirely absent from
+//! this tree, on the binary side as well as the library side. [`LabelHash::matches`]
+//! does have a real caller in the meantime: [`LabelMatcher`](crate::classification::matcher::LabelMatcher)
+//! special-cases the single-label descendant selector -- by far the common
+//! case -- by hash-rejecting candidate keys instead of building a full
+//! Aho-Corasick trie just to match one pattern.
+
+const PRIME_1: u64 = 0x9E37_79B1_85EB_CA87;
+const PRIME_2: u64 = 0xC2B2_AE3D_27D4_EB4F;
+const PRIME_3: u64 = 0x1656_67B1_9E37_79F9;
+
+/// Fixed secret material the lane mixing step folds input against.
+///
+/// Plays the same role as XXH3's `kSecret`: arbitrary, fixed, high-entropy
+/// bytes that decorrelate the hash from the raw input bits. It doesn't need to
+/// match upstream xxHash's secret, only to be fixed so hashes are reproducible
+/// across calls.
+#[rustfmt::skip]
+static SECRET: [u8; 192] = [
+    0x39, 0x0C, 0x8C, 0x7D, 0x72, 0x47, 0x34, 0x2C, 0xD8, 0x10, 0x0F, 0x2F, 0x6F, 0x77, 0x0D, 0x65,
+    0xD6, 0x70, 0xE5, 0x8E, 0x03, 0x51, 0xD8, 0xAE, 0x8E, 0x4F, 0x6E, 0xAC, 0x34, 0x2F, 0xC2, 0x31,
+    0xB7, 0xB0, 0x87, 0x16, 0xEB, 0x3F, 0xC1, 0x28, 0x96, 0xB9, 0x62, 0x23, 0x17, 0x74, 0x94, 0x28,
+    0x77, 0x33, 0xC2, 0x8E, 0xE8, 0xBA, 0x53, 0xBD, 0xB5, 0x6B, 0x88, 0x24, 0x57, 0x7D, 0x53, 0xEC,
+    0xC2, 0x8A, 0x70, 0xA6, 0x1C, 0x75, 0x10, 0xA1, 0xCD, 0x89, 0x21, 0x6C, 0xA1, 0x6C, 0xFF, 0xCA,
+    0xEA, 0x49, 0x87, 0x47, 0x7E, 0x86, 0xDB, 0xCC, 0xB9, 0x70, 0x46, 0xFC, 0x2E, 0x18, 0x38, 0x4E,
+    0x51, 0xD8, 0x20, 0xC5, 0xC3, 0xEF, 0x80, 0x05, 0x3A, 0x88, 0xAE, 0x39, 0x96, 0xDE, 0x50, 0xE8,
+    0x01, 0x86, 0x5B, 0x36, 0x98, 0x65, 0x4E, 0xBF, 0x52, 0x00, 0xA5, 0xFA, 0x09, 0x39, 0xB9, 0x9D,
+    0x7A, 0x1D, 0x7B, 0x28, 0x2B, 0xF8, 0x23, 0x40, 0x41, 0xF3, 0x54, 0x87, 0xD8, 0x6C, 0x66, 0x9F,
+    0xCC, 0xBF, 0xE0, 0xE7, 0x3D, 0x7E, 0x73, 0x20, 0xAD, 0x0A, 0x75, 0x70, 0x03, 0x24, 0x1E, 0x75,
+    0x22, 0x10, 0xA9, 0x24, 0x79, 0x8E, 0xF8, 0x6D, 0x43, 0xF2, 0x7C, 0xF2, 0xD0, 0x61, 0x30, 0x31,
+    0xDC, 0xB5, 0xD8, 0xD2, 0xEF, 0x1B, 0x32, 0x1F, 0xCE, 0xAD, 0x37, 0x7F, 0x62, 0x61, 0xE5, 0x47,
+];
+
+/// A precomputed hash of a target member-name label.
+///
+/// Built once at query-compile time via [`LabelHash::of`], then reused at
+/// execution time by [`LabelHash::matches`] to fast-reject every candidate key
+/// whose hash differs, without a byte-by-byte comparison.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LabelHash(u64);
+
+impl LabelHash {
+    /// Precompute the hash of `label`.
+    #[must_use]
+    #[inline]
+    pub fn of(label: &[u8]) -> Self {
+        Self(hash64(label))
+    }
+
+    /// Test whether `candidate` — the bytes of a JSON key as delimited by its
+    /// surrounding quotes — is the same label this hash was built from.
+    ///
+    /// Hashes `candidate` and compares against the stored hash first; `label`
+    /// (the same bytes originally passed to [`LabelHash::of`]) is only
+    /// compared byte-by-byte if the hashes match, so a miss never pays for
+    /// more than one hash computation.
+    #[must_use]
+    #[inline]
+    pub fn matches(&self, candidate: &[u8], label: &[u8]) -> bool {
+        hash64(candidate) == self.0 && candidate == label
+    }
+}
+
+/// A 64-bit XXH3-style hash of `data`.
+#[must_use]
+fn hash64(data: &[u8]) -> u64 {
+    if data.len() <= 16 {
+        hash_short(data)
+    } else {
+        hash_long(data)
+    }
+}
+
+/// Hashes inputs of 16 bytes or fewer by mixing the length with up to two
+/// overlapping 8-byte, zero-padded reads (one from the front, one from the
+/// back), so short and empty keys are handled without any looping.
+fn hash_short(data: &[u8]) -> u64 {
+    let len = data.len() as u64;
+    let lo = read_padded_u64(data, 0) ^ read_u64_le(&SECRET[0..8]);
+    let hi = read_padded_u64(data, data.len().saturating_sub(8)) ^ read_u64_le(&SECRET[8..16]);
+
+    let mixed = lo.wrapping_add(len).wrapping_add(hi.rotate_left(17));
+    avalanche(mixed)
+}
+
+/// Hashes inputs longer than 16 bytes by folding each 16-byte lane against a
+/// rotating window of [`SECRET`] and accumulating, then finishing off any
+/// remaining tail bytes the same way the short path handles a whole input.
+fn hash_long(data: &[u8]) -> u64 {
+    let mut acc = PRIME_1 ^ (data.len() as u64).wrapping_mul(PRIME_2);
+
+    let mut lanes = data.chunks_exact(16);
+    for (i, lane) in lanes.by_ref().enumerate() {
+        let secret_offset = (i * 16) % (SECRET.len() - 16);
+        let secret_lo = read_u64_le(&SECRET[secret_offset..secret_offset + 8]);
+        let secret_hi = read_u64_le(&SECRET[secret_offset + 8..secret_offset + 16]);
+
+        let lane_lo = read_u64_le(&lane[0..8]) ^ secret_lo;
+        let lane_hi = read_u64_le(&lane[8..16]) ^ secret_hi;
+
+        // The core XXH3 mixing step: treat the XOR-folded halves as operands
+        // of a 64x64-to-128-bit multiplication and fold the product's two
+        // halves back into a single 64-bit lane result.
+        let product = u128::from(lane_lo).wrapping_mul(u128::from(lane_hi));
+        let folded = (product as u64) ^ ((product >> 64) as u64);
+
+        acc = acc.wrapping_add(folded);
+        acc = acc.rotate_left(27).wrapping_mul(PRIME_1);
+    }
+
+    let tail = lanes.remainder();
+    if !tail.is_empty() {
+        let lo = read_padded_u64(tail, 0) ^ read_u64_le(&SECRET[0..8]);
+        let hi = read_padded_u64(tail, tail.len().saturating_sub(8)) ^ read_u64_le(&SECRET[8..16]);
+
+        acc ^= lo.wrapping_mul(PRIME_2);
+        acc = acc.rotate_left(31).wrapping_mul(PRIME_3);
+        acc ^= hi;
+    }
+
+    avalanche(acc)
+}
+
+/// XXH3's finishing mix: spreads entropy across every bit of the accumulator
+/// so nearby accumulator values don't produce nearby hashes.
+fn avalanche(mut x: u64) -> u64 {
+    x ^= x >> 37;
+    x = x.wrapping_mul(PRIME_3);
+    x ^= x >> 32;
+    x = x.wrapping_mul(PRIME_1);
+    x ^= x >> 29;
+    x
+}
+
+fn read_u64_le(bytes: &[u8]) -> u64 {
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(&bytes[..8]);
+    u64::from_le_bytes(buf)
+}
+
+/// Reads up to 8 bytes starting at `offset` as a little-endian `u64`,
+/// zero-padding past the end of `data` so inputs shorter than 8 bytes (from
+/// `offset`) still produce a well-defined, stable value.
+fn read_padded_u64(data: &[u8], offset: usize) -> u64 {
+    let available = &data[offset..];
+    let n = available.len().min(8);
+    let mut buf = [0u8; 8];
+    buf[..n].copy_from_slice(&available[..n]);
+    u64::from_le_bytes(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_is_deterministic() {
+        assert_eq!(hash64(b"name"), hash64(b"name"));
+        assert_eq!(hash64(b""), hash64(b""));
+    }
+
+    #[test]
+    fn different_labels_hash_differently() {
+        let labels: [&[u8]; 7] = [
+            b"",
+            b"a",
+            b"id",
+            b"name",
+            b"isbn",
+            b"a much longer member name than the others",
+            b"a much longer member name than the others!",
+        ];
+
+        for i in 0..labels.len() {
+            for j in (i + 1)..labels.len() {
+                assert_ne!(
+                    hash64(labels[i]),
+                    hash64(labels[j]),
+                    "collision between {:?} and {:?}",
+                    labels[i],
+                    labels[j]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn covers_the_short_and_long_length_boundary() {
+        // 16 bytes takes the short path, 17 takes the long one; both must
+        // still be internally consistent (deterministic, distinct).
+        let sixteen = b"0123456789abcdef";
+        let seventeen = b"0123456789abcdefg";
+
+        assert_eq!(hash64(sixteen), hash64(sixteen));
+        assert_eq!(hash64(seventeen), hash64(seventeen));
+        assert_ne!(hash64(sixteen), hash64(seventeen));
+    }
+
+    #[test]
+    fn label_hash_matches_identical_bytes_only() {
+        let label = b"price";
+        let hash = LabelHash::of(label);
+
+        assert!(hash.matches(b"price", label));
+        assert!(!hash.matches(b"prices", label));
+        assert!(!hash.matches(b"rice", label));
+        assert!(!hash.matches(b"", label));
+    }
+}
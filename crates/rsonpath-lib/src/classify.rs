@@ -7,44 +7,44 @@
 //!
 //! # Examples
 //! ```rust
-//! use rsonpath_lib::classify::{Structural, classify_structural_characters};
+//! use rsonpath_lib::classify::{BracketType, Structural, classify_structural_characters};
 //! use aligners::{alignment, AlignedBytes};
 //!
 //! let json = r#"{"x": [{"y": 42}, {}]}""#;
 //! let aligned = AlignedBytes::<alignment::Twice<rsonpath_lib::BlockAlignment>>::new_padded(json.as_bytes());
 //! let expected = vec![
-//!     Structural::Opening(0),
+//!     Structural::Opening(BracketType::Object, 0),
 //!     Structural::Colon(4),
-//!     Structural::Opening(6),
-//!     Structural::Opening(7),
+//!     Structural::Opening(BracketType::Array, 6),
+//!     Structural::Opening(BracketType::Object, 7),
 //!     Structural::Colon(11),
-//!     Structural::Closing(15),
-//!     Structural::Opening(18),
-//!     Structural::Closing(19),
-//!     Structural::Closing(20),
-//!     Structural::Closing(21)
+//!     Structural::Closing(BracketType::Object, 15),
+//!     Structural::Opening(BracketType::Object, 18),
+//!     Structural::Closing(BracketType::Object, 19),
+//!     Structural::Closing(BracketType::Array, 20),
+//!     Structural::Closing(BracketType::Object, 21)
 //! ];
 //! let quote_classifier = rsonpath_lib::quotes::classify_quoted_sequences(&aligned);
 //! let actual = classify_structural_characters(quote_classifier).collect::<Vec<Structural>>();
 //! assert_eq!(expected, actual);
 //! ```
 //! ```rust
-//! use rsonpath_lib::classify::{Structural, classify_structural_characters};
+//! use rsonpath_lib::classify::{BracketType, Structural, classify_structural_characters};
 //! use aligners::{alignment, AlignedBytes};
 //!
 //! let json = r#"{"x": "[\"\"]"}""#;
 //! let aligned = AlignedBytes::<alignment::Twice<rsonpath_lib::BlockAlignment>>::new_padded(json.as_bytes());
 //! let expected = vec![
-//!     Structural::Opening(0),
+//!     Structural::Opening(BracketType::Object, 0),
 //!     Structural::Colon(4),
-//!     Structural::Closing(14)
+//!     Structural::Closing(BracketType::Object, 14)
 //! ];
 //! let quote_classifier = rsonpath_lib::quotes::classify_quoted_sequences(&aligned);
 //! let actual = classify_structural_characters(quote_classifier).collect::<Vec<Structural>>();
 //! assert_eq!(expected, actual);
 //! ```
 
-use std::marker::PhantomData;
+use core::marker::PhantomData;
 
 use crate::{
     debug,
@@ -54,15 +54,26 @@ use crate::{
 use cfg_if::cfg_if;
 use replace_with::replace_with_or_abort;
 
+/// Distinguishes the two kinds of bracket pairs a [`Structural::Opening`] or
+/// [`Structural::Closing`] can represent, so callers resolving member-name or
+/// array-index selectors don't need to re-read the source byte to tell them apart.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum BracketType {
+    /// A `{` or `}` brace, delimiting an object.
+    Object,
+    /// A `[` or `]` bracket, delimiting an array.
+    Array,
+}
+
 /// Defines structural characters in JSON documents.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum Structural {
-    /// Represents either the closing brace '{' or closing bracket '['.
-    Closing(usize),
+    /// Represents either the closing brace '}' or closing bracket ']'.
+    Closing(BracketType, usize),
     /// Represents the colon ':' character.
     Colon(usize),
-    /// Represents either the opening brace '}' or opening bracket ']'.
-    Opening(usize),
+    /// Represents either the opening brace '{' or opening bracket '['.
+    Opening(BracketType, usize),
     /// Represents the comma ',' character.
     Comma(usize),
 }
@@ -75,7 +86,7 @@ impl Structural {
     #[must_use]
     pub fn idx(self) -> usize {
         match self {
-            Closing(idx) | Colon(idx) | Opening(idx) | Comma(idx) => idx,
+            Closing(_, idx) | Colon(idx) | Opening(_, idx) | Comma(idx) => idx,
         }
     }
 
@@ -95,9 +106,9 @@ impl Structural {
     #[must_use]
     pub fn offset(self, amount: usize) -> Self {
         match self {
-            Closing(idx) => Closing(idx + amount),
+            Closing(bracket_type, idx) => Closing(bracket_type, idx + amount),
             Colon(idx) => Colon(idx + amount),
-            Opening(idx) => Opening(idx + amount),
+            Opening(bracket_type, idx) => Opening(bracket_type, idx + amount),
             Comma(idx) => Comma(idx + amount),
         }
     }
@@ -170,7 +181,7 @@ where
     }
 }
 
-impl<'b, Q, I> std::ops::Deref for ClassifierWithSkipping<'b, Q, I>
+impl<'b, Q, I> core::ops::Deref for ClassifierWithSkipping<'b, Q, I>
 where
     Q: QuoteClassifiedIterator<'b>,
     I: StructuralIterator<'b, Q>,
@@ -182,7 +193,7 @@ where
     }
 }
 
-impl<'b, Q, I> std::ops::DerefMut for ClassifierWithSkipping<'b, Q, I>
+impl<'b, Q, I> core::ops::DerefMut for ClassifierWithSkipping<'b, Q, I>
 where
     Q: QuoteClassifiedIterator<'b>,
     I: StructuralIterator<'b, Q>,
@@ -208,6 +219,10 @@ pub trait StructuralIterator<'a, I: QuoteClassifiedIterator<'a>>:
     fn turn_commas_on(&mut self, idx: usize);
 
     fn turn_commas_off(&mut self);
+
+    fn turn_colons_on(&mut self, idx: usize);
+
+    fn turn_colons_off(&mut self);
 }
 
 cfg_if! {
@@ -233,9 +248,102 @@ cfg_if! {
             SequentialClassifier::resume(state)
         }
     }
-    else if #[cfg(simd = "avx2")] {
+    else if #[cfg(any(target_arch = "x86", target_arch = "x86_64"))] {
         mod avx2;
+        mod nosimd;
+        mod sse2;
         use avx2::Avx2Classifier;
+        use nosimd::SequentialClassifier;
+        use sse2::Sse2Classifier;
+
+        /// Dispatches to whichever backend the host CPU supports, chosen once at
+        /// construction/resume time: [`Avx2Classifier`] if `avx2` is available, else
+        /// [`Sse2Classifier`] if `sse2` is, else the portable [`SequentialClassifier`].
+        ///
+        /// This lets a single compiled binary run on pre-AVX2 x86 hosts without requiring
+        /// a rebuild with SIMD disabled. Feature detection is deterministic for the
+        /// lifetime of the process, so `resume` always re-derives the same backend that
+        /// produced the [`ResumeClassifierState`] being resumed, which matters because
+        /// each backend reads the input in a different lane width (AVX2 needs
+        /// `Twice<BlockAlignment>` padding, SSE2 needs half that).
+        pub(crate) enum DispatchedClassifier<'a, I: QuoteClassifiedIterator<'a>> {
+            Avx2(Avx2Classifier<'a, I>),
+            Sse2(Sse2Classifier<'a, I>),
+            Nosimd(SequentialClassifier<'a, I>),
+        }
+
+        impl<'a, I: QuoteClassifiedIterator<'a>> Iterator for DispatchedClassifier<'a, I> {
+            type Item = Structural;
+
+            #[inline(always)]
+            fn next(&mut self) -> Option<Structural> {
+                match self {
+                    Self::Avx2(c) => c.next(),
+                    Self::Sse2(c) => c.next(),
+                    Self::Nosimd(c) => c.next(),
+                }
+            }
+        }
+
+        impl<'a, I: QuoteClassifiedIterator<'a>> core::iter::FusedIterator for DispatchedClassifier<'a, I> {}
+
+        impl<'a, I: QuoteClassifiedIterator<'a>> StructuralIterator<'a, I> for DispatchedClassifier<'a, I> {
+            #[inline(always)]
+            fn stop(self) -> ResumeClassifierState<'a, I> {
+                match self {
+                    Self::Avx2(c) => c.stop(),
+                    Self::Sse2(c) => c.stop(),
+                    Self::Nosimd(c) => c.stop(),
+                }
+            }
+
+            #[inline(always)]
+            fn resume(state: ResumeClassifierState<'a, I>) -> Self {
+                if is_x86_feature_detected!("avx2") {
+                    Self::Avx2(Avx2Classifier::resume(state))
+                } else if is_x86_feature_detected!("sse2") {
+                    Self::Sse2(Sse2Classifier::resume(state))
+                } else {
+                    Self::Nosimd(SequentialClassifier::resume(state))
+                }
+            }
+
+            #[inline(always)]
+            fn turn_commas_on(&mut self, idx: usize) {
+                match self {
+                    Self::Avx2(c) => c.turn_commas_on(idx),
+                    Self::Sse2(c) => c.turn_commas_on(idx),
+                    Self::Nosimd(c) => c.turn_commas_on(idx),
+                }
+            }
+
+            #[inline(always)]
+            fn turn_commas_off(&mut self) {
+                match self {
+                    Self::Avx2(c) => c.turn_commas_off(),
+                    Self::Sse2(c) => c.turn_commas_off(),
+                    Self::Nosimd(c) => c.turn_commas_off(),
+                }
+            }
+
+            #[inline(always)]
+            fn turn_colons_on(&mut self, idx: usize) {
+                match self {
+                    Self::Avx2(c) => c.turn_colons_on(idx),
+                    Self::Sse2(c) => c.turn_colons_on(idx),
+                    Self::Nosimd(c) => c.turn_colons_on(idx),
+                }
+            }
+
+            #[inline(always)]
+            fn turn_colons_off(&mut self) {
+                match self {
+                    Self::Avx2(c) => c.turn_colons_off(),
+                    Self::Sse2(c) => c.turn_colons_off(),
+                    Self::Nosimd(c) => c.turn_colons_off(),
+                }
+            }
+        }
 
         /// Walk through the JSON document represented by `bytes` and iterate over all
         /// occurrences of structural characters in it.
@@ -243,7 +351,13 @@ cfg_if! {
         pub fn classify_structural_characters<'a, I: QuoteClassifiedIterator<'a>>(
             iter: I,
         ) -> impl StructuralIterator<'a, I> {
-            Avx2Classifier::new(iter)
+            if is_x86_feature_detected!("avx2") {
+                DispatchedClassifier::Avx2(Avx2Classifier::new(iter))
+            } else if is_x86_feature_detected!("sse2") {
+                DispatchedClassifier::Sse2(Sse2Classifier::new(iter))
+            } else {
+                DispatchedClassifier::Nosimd(SequentialClassifier::new(iter))
+            }
         }
 
         /// Resume classification using a state retrieved from a previously
@@ -252,7 +366,7 @@ cfg_if! {
         pub fn resume_structural_classification<'a, I: QuoteClassifiedIterator<'a>>(
             state: ResumeClassifierState<'a, I>
         ) -> impl StructuralIterator<'a, I> {
-            Avx2Classifier::resume(state)
+            DispatchedClassifier::resume(state)
         }
     }
     else {
@@ -260,6 +374,11 @@ cfg_if! {
     }
 }
 
+#[cfg(feature = "std")]
+pub mod streaming;
+#[cfg(feature = "std")]
+pub use streaming::StreamingClassifier;
+
 #[cfg(test)]
 mod tests {
     use crate::quotes::classify_quoted_sequences;
@@ -277,17 +396,17 @@ mod tests {
 
         let mut classifier = classify_structural_characters(quotes);
 
-        assert_eq!(Some(Opening(0)), classifier.next());
+        assert_eq!(Some(Opening(BracketType::Object, 0)), classifier.next());
         assert_eq!(Some(Colon(4)), classifier.next());
-        assert_eq!(Some(Opening(6)), classifier.next());
+        assert_eq!(Some(Opening(BracketType::Array, 6)), classifier.next());
 
         let resume_state = classifier.stop();
 
         let mut resumed_classifier = resume_structural_classification(resume_state);
 
-        assert_eq!(Some(Opening(15)), resumed_classifier.next());
+        assert_eq!(Some(Opening(BracketType::Object, 15)), resumed_classifier.next());
         assert_eq!(Some(Colon(20)), resumed_classifier.next());
-        assert_eq!(Some(Opening(22)), resumed_classifier.next());
+        assert_eq!(Some(Opening(BracketType::Object, 22)), resumed_classifier.next());
         assert_eq!(Some(Colon(27)), resumed_classifier.next());
     }
 
@@ -302,9 +421,9 @@ mod tests {
         let mut classifier = classify_structural_characters(quotes);
         classifier.turn_commas_on(0);
 
-        assert_eq!(Some(Opening(0)), classifier.next());
+        assert_eq!(Some(Opening(BracketType::Object, 0)), classifier.next());
         assert_eq!(Some(Colon(4)), classifier.next());
-        assert_eq!(Some(Opening(6)), classifier.next());
+        assert_eq!(Some(Opening(BracketType::Array, 6)), classifier.next());
         assert_eq!(Some(Comma(9)), classifier.next());
         assert_eq!(Some(Comma(13)), classifier.next());
 
@@ -313,9 +432,9 @@ mod tests {
         let mut resumed_classifier = resume_structural_classification(resume_state);
         resumed_classifier.turn_commas_on(14);
 
-        assert_eq!(Some(Opening(15)), resumed_classifier.next());
+        assert_eq!(Some(Opening(BracketType::Object, 15)), resumed_classifier.next());
         assert_eq!(Some(Colon(20)), resumed_classifier.next());
-        assert_eq!(Some(Opening(22)), resumed_classifier.next());
+        assert_eq!(Some(Opening(BracketType::Object, 22)), resumed_classifier.next());
         assert_eq!(Some(Colon(27)), resumed_classifier.next());
         assert_eq!(Some(Comma(30)), resumed_classifier.next());
     }
@@ -5,14 +5,16 @@ struct Block<'a> {
     quote_classified: QuoteClassifiedBlock<'a>,
     idx: usize,
     are_commas_on: bool,
+    are_colons_on: bool,
 }
 
 impl<'a> Block<'a> {
-    fn new(quote_classified_block: QuoteClassifiedBlock<'a>, are_commas_on: bool) -> Self {
+    fn new(quote_classified_block: QuoteClassifiedBlock<'a>, are_commas_on: bool, are_colons_on: bool) -> Self {
         Self {
             quote_classified: quote_classified_block,
             idx: 0,
             are_commas_on,
+            are_colons_on,
         }
     }
 
@@ -20,11 +22,13 @@ impl<'a> Block<'a> {
         quote_classified_block: QuoteClassifiedBlock<'a>,
         idx: usize,
         are_commas_on: bool,
+        are_colons_on: bool,
     ) -> Self {
         Self {
             quote_classified: quote_classified_block,
             idx,
             are_commas_on,
+            are_colons_on,
         }
     }
 }
@@ -42,10 +46,12 @@ impl<'a> Iterator for Block<'a> {
 
             if !is_quoted {
                 match character {
-                    b':' => return Some(Colon(self.idx - 1)),
-                    b'[' | b'{' => return Some(Opening(self.idx - 1)),
+                    b':' if self.are_colons_on => return Some(Colon(self.idx - 1)),
+                    b'{' => return Some(Opening(BracketType::Object, self.idx - 1)),
+                    b'[' => return Some(Opening(BracketType::Array, self.idx - 1)),
                     b',' if self.are_commas_on => return Some(Comma(self.idx - 1)),
-                    b']' | b'}' => return Some(Closing(self.idx - 1)),
+                    b'}' => return Some(Closing(BracketType::Object, self.idx - 1)),
+                    b']' => return Some(Closing(BracketType::Array, self.idx - 1)),
                     _ => (),
                 }
             }
@@ -59,6 +65,7 @@ pub(crate) struct SequentialClassifier<'a, I: QuoteClassifiedIterator<'a>> {
     iter: I,
     block: Option<Block<'a>>,
     are_commas_on: bool,
+    are_colons_on: bool,
 }
 
 impl<'a, I: QuoteClassifiedIterator<'a>> SequentialClassifier<'a, I> {
@@ -68,6 +75,7 @@ impl<'a, I: QuoteClassifiedIterator<'a>> SequentialClassifier<'a, I> {
             iter,
             block: None,
             are_commas_on: false,
+            are_colons_on: true,
         }
     }
 }
@@ -82,7 +90,7 @@ impl<'a, I: QuoteClassifiedIterator<'a>> Iterator for SequentialClassifier<'a, I
         while item.is_none() {
             match self.iter.next() {
                 Some(block) => {
-                    let mut block = Block::new(block, self.are_commas_on);
+                    let mut block = Block::new(block, self.are_commas_on, self.are_colons_on);
                     item = block.next();
                     self.block = Some(block);
                 }
@@ -94,7 +102,7 @@ impl<'a, I: QuoteClassifiedIterator<'a>> Iterator for SequentialClassifier<'a, I
     }
 }
 
-impl<'a, I: QuoteClassifiedIterator<'a>> std::iter::FusedIterator for SequentialClassifier<'a, I> {}
+impl<'a, I: QuoteClassifiedIterator<'a>> core::iter::FusedIterator for SequentialClassifier<'a, I> {}
 
 impl<'a, I: QuoteClassifiedIterator<'a>> StructuralIterator<'a, I> for SequentialClassifier<'a, I> {
     fn turn_commas_on(&mut self, idx: usize) {
@@ -106,7 +114,7 @@ impl<'a, I: QuoteClassifiedIterator<'a>> StructuralIterator<'a, I> for Sequentia
                 let block_idx = (idx + 1) % quote_classified_block.len();
 
                 if block_idx != 0 {
-                    let mut new_block = Block::from_idx(quote_classified_block, block_idx, true);
+                    let new_block = Block::from_idx(quote_classified_block, block_idx, true, block.are_colons_on);
                     self.block = Some(new_block);
                 }
             }
@@ -117,9 +125,25 @@ impl<'a, I: QuoteClassifiedIterator<'a>> StructuralIterator<'a, I> for Sequentia
         self.are_commas_on = false;
     }
 
-    fn turn_colons_on(&mut self, idx: usize) {}
+    fn turn_colons_on(&mut self, idx: usize) {
+        if !self.are_colons_on {
+            self.are_colons_on = true;
 
-    fn turn_colons_off(&mut self) {}
+            if let Some(block) = self.block.take() {
+                let quote_classified_block = block.quote_classified;
+                let block_idx = (idx + 1) % quote_classified_block.len();
+
+                if block_idx != 0 {
+                    let new_block = Block::from_idx(quote_classified_block, block_idx, block.are_commas_on, true);
+                    self.block = Some(new_block);
+                }
+            }
+        }
+    }
+
+    fn turn_colons_off(&mut self) {
+        self.are_colons_on = false;
+    }
 
     fn stop(self) -> ResumeClassifierState<'a, I> {
         let block = self.block.map(|b| ResumeClassifierBlockState {
@@ -139,8 +163,10 @@ impl<'a, I: QuoteClassifiedIterator<'a>> StructuralIterator<'a, I> for Sequentia
                 quote_classified: b.block,
                 idx: b.idx,
                 are_commas_on: false,
+                are_colons_on: true,
             }),
             are_commas_on: false,
+            are_colons_on: true,
         }
     }
 }
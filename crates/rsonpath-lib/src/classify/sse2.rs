@@ -0,0 +1,255 @@
+//! SSE2 structural classifier, processing the block in 16-byte chunks.
+//!
+//! Half the lane width of [`super::avx2::Avx2Classifier`], so it needs twice as many compares per
+//! block, but it only requires `sse2`, which is part of the x86_64 baseline and present on every
+//! pre-AVX2 x86 host the portable [`SequentialClassifier`](super::nosimd::SequentialClassifier)
+//! would otherwise have to serve.
+use super::*;
+use crate::quotes::{QuoteClassifiedBlock, ResumeClassifierBlockState, ResumeClassifierState};
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+/// Width, in bytes, of the vector register this classifier compares against.
+const SSE2_WIDTH: usize = 16;
+
+/// Per-block bitmasks of candidate structural characters, already filtered by
+/// `within_quotes_mask` so bits set here are never inside a quoted string.
+///
+/// Opening and closing are each split by [`BracketType`] rather than merged, since the merge
+/// would force callers to re-read the source byte to tell an object brace from an array bracket.
+struct StructuralMasks {
+    opening_object: u64,
+    opening_array: u64,
+    closing_object: u64,
+    closing_array: u64,
+    comma: u64,
+    colon: u64,
+}
+
+#[target_feature(enable = "sse2")]
+unsafe fn classify_structural_masks(bytes: &[u8], within_quotes_mask: u64) -> StructuralMasks {
+    let mut opening_object = 0_u64;
+    let mut opening_array = 0_u64;
+    let mut closing_object = 0_u64;
+    let mut closing_array = 0_u64;
+    let mut comma = 0_u64;
+    let mut colon = 0_u64;
+
+    for (chunk_idx, chunk) in bytes.chunks_exact(SSE2_WIDTH).enumerate() {
+        let vector = _mm_loadu_si128(chunk.as_ptr().cast());
+        let shift = chunk_idx * SSE2_WIDTH;
+
+        let opening_brace = _mm_cmpeq_epi8(vector, _mm_set1_epi8(b'{' as i8));
+        let opening_bracket = _mm_cmpeq_epi8(vector, _mm_set1_epi8(b'[' as i8));
+        let closing_brace = _mm_cmpeq_epi8(vector, _mm_set1_epi8(b'}' as i8));
+        let closing_bracket = _mm_cmpeq_epi8(vector, _mm_set1_epi8(b']' as i8));
+        let commas = _mm_cmpeq_epi8(vector, _mm_set1_epi8(b',' as i8));
+        let colons = _mm_cmpeq_epi8(vector, _mm_set1_epi8(b':' as i8));
+
+        opening_object |= (_mm_movemask_epi8(opening_brace) as u32 as u64) << shift;
+        opening_array |= (_mm_movemask_epi8(opening_bracket) as u32 as u64) << shift;
+        closing_object |= (_mm_movemask_epi8(closing_brace) as u32 as u64) << shift;
+        closing_array |= (_mm_movemask_epi8(closing_bracket) as u32 as u64) << shift;
+        comma |= (_mm_movemask_epi8(commas) as u32 as u64) << shift;
+        colon |= (_mm_movemask_epi8(colons) as u32 as u64) << shift;
+    }
+
+    let not_quoted = !within_quotes_mask;
+    StructuralMasks {
+        opening_object: opening_object & not_quoted,
+        opening_array: opening_array & not_quoted,
+        closing_object: closing_object & not_quoted,
+        closing_array: closing_array & not_quoted,
+        comma: comma & not_quoted,
+        colon: colon & not_quoted,
+    }
+}
+
+/// Mask of all bit positions strictly below `idx`, used to skip already-consumed bits.
+#[inline(always)]
+fn consumed_mask(idx: usize) -> u64 {
+    if idx == 0 {
+        0
+    } else if idx >= 64 {
+        u64::MAX
+    } else {
+        (1_u64 << idx) - 1
+    }
+}
+
+struct Block<'a> {
+    quote_classified: QuoteClassifiedBlock<'a>,
+    masks: StructuralMasks,
+    idx: usize,
+    are_commas_on: bool,
+    are_colons_on: bool,
+}
+
+impl<'a> Block<'a> {
+    fn new(quote_classified_block: QuoteClassifiedBlock<'a>, are_commas_on: bool, are_colons_on: bool) -> Self {
+        Self::from_idx(quote_classified_block, 0, are_commas_on, are_colons_on)
+    }
+
+    fn from_idx(
+        quote_classified_block: QuoteClassifiedBlock<'a>,
+        idx: usize,
+        are_commas_on: bool,
+        are_colons_on: bool,
+    ) -> Self {
+        // SAFETY: this classifier is only ever constructed behind an `is_x86_feature_detected!("sse2")` check.
+        let masks =
+            unsafe { classify_structural_masks(quote_classified_block.block, quote_classified_block.within_quotes_mask) };
+        Self {
+            quote_classified: quote_classified_block,
+            masks,
+            idx,
+            are_commas_on,
+            are_colons_on,
+        }
+    }
+}
+
+impl<'a> Iterator for Block<'a> {
+    type Item = Structural;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let active_mask = self.masks.opening_object
+            | self.masks.opening_array
+            | self.masks.closing_object
+            | self.masks.closing_array
+            | if self.are_commas_on { self.masks.comma } else { 0 }
+            | if self.are_colons_on { self.masks.colon } else { 0 };
+        let remaining = active_mask & !consumed_mask(self.idx);
+
+        if remaining == 0 {
+            return None;
+        }
+
+        let bit = remaining.trailing_zeros() as usize;
+        let bitmask = 1_u64 << bit;
+        self.idx = bit + 1;
+
+        if self.masks.opening_object & bitmask != 0 {
+            Some(Opening(BracketType::Object, bit))
+        } else if self.masks.opening_array & bitmask != 0 {
+            Some(Opening(BracketType::Array, bit))
+        } else if self.masks.closing_object & bitmask != 0 {
+            Some(Closing(BracketType::Object, bit))
+        } else if self.masks.closing_array & bitmask != 0 {
+            Some(Closing(BracketType::Array, bit))
+        } else if self.masks.comma & bitmask != 0 {
+            Some(Comma(bit))
+        } else {
+            Some(Colon(bit))
+        }
+    }
+}
+
+pub(crate) struct Sse2Classifier<'a, I: QuoteClassifiedIterator<'a>> {
+    iter: I,
+    block: Option<Block<'a>>,
+    are_commas_on: bool,
+    are_colons_on: bool,
+}
+
+impl<'a, I: QuoteClassifiedIterator<'a>> Sse2Classifier<'a, I> {
+    #[inline(always)]
+    pub(crate) fn new(iter: I) -> Self {
+        Self {
+            iter,
+            block: None,
+            are_commas_on: false,
+            are_colons_on: true,
+        }
+    }
+}
+
+impl<'a, I: QuoteClassifiedIterator<'a>> Iterator for Sse2Classifier<'a, I> {
+    type Item = Structural;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Structural> {
+        let mut item = self.block.as_mut().and_then(Iterator::next);
+
+        while item.is_none() {
+            match self.iter.next() {
+                Some(block) => {
+                    let mut block = Block::new(block, self.are_commas_on, self.are_colons_on);
+                    item = block.next();
+                    self.block = Some(block);
+                }
+                None => return None,
+            }
+        }
+
+        item.map(|x| x.offset(self.iter.get_offset()))
+    }
+}
+
+impl<'a, I: QuoteClassifiedIterator<'a>> core::iter::FusedIterator for Sse2Classifier<'a, I> {}
+
+impl<'a, I: QuoteClassifiedIterator<'a>> StructuralIterator<'a, I> for Sse2Classifier<'a, I> {
+    fn turn_commas_on(&mut self, idx: usize) {
+        if !self.are_commas_on {
+            self.are_commas_on = true;
+
+            if let Some(block) = self.block.take() {
+                let quote_classified_block = block.quote_classified;
+                let block_idx = (idx + 1) % quote_classified_block.len();
+
+                if block_idx != 0 {
+                    let new_block = Block::from_idx(quote_classified_block, block_idx, true, block.are_colons_on);
+                    self.block = Some(new_block);
+                }
+            }
+        }
+    }
+
+    fn turn_commas_off(&mut self) {
+        self.are_commas_on = false;
+    }
+
+    fn turn_colons_on(&mut self, idx: usize) {
+        if !self.are_colons_on {
+            self.are_colons_on = true;
+
+            if let Some(block) = self.block.take() {
+                let quote_classified_block = block.quote_classified;
+                let block_idx = (idx + 1) % quote_classified_block.len();
+
+                if block_idx != 0 {
+                    let new_block = Block::from_idx(quote_classified_block, block_idx, block.are_commas_on, true);
+                    self.block = Some(new_block);
+                }
+            }
+        }
+    }
+
+    fn turn_colons_off(&mut self) {
+        self.are_colons_on = false;
+    }
+
+    fn stop(self) -> ResumeClassifierState<'a, I> {
+        let block = self.block.map(|b| ResumeClassifierBlockState {
+            block: b.quote_classified,
+            idx: b.idx,
+        });
+        ResumeClassifierState {
+            iter: self.iter,
+            block,
+        }
+    }
+
+    fn resume(state: ResumeClassifierState<'a, I>) -> Self {
+        Self {
+            iter: state.iter,
+            block: state
+                .block
+                .map(|b| Block::from_idx(b.block, b.idx, false, true)),
+            are_commas_on: false,
+            are_colons_on: true,
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! Structural classification over an [`io::Read`] source, for documents too large (or too
+//! slow-arriving) to materialize into a single buffer up front.
+//!
+//! The classifier reads the source into a reusable buffer and yields [`Structural`] values with
+//! offsets relative to the start of the whole stream, not the current buffer. A structural byte
+//! is never split across a refill, but a quoted string can span arbitrarily many reads, so each
+//! call keeps reading until the buffer ends outside a string (or the source ends), and only then
+//! classifies the buffer once and clears it. No in-string/escape state is carried from one chunk
+//! to the next, so nothing is threaded through [`ResumeClassifierState`] by hand; that state
+//! borrows the exact [`AlignedBytes`] allocation it was produced from and cannot survive the
+//! buffer being grown.
+use super::{classify_structural_characters, Structural};
+use crate::quotes::classify_quoted_sequences;
+use crate::BlockAlignment;
+use aligners::{alignment::Twice, AlignedBytes};
+use std::io;
+
+/// Bytes accumulated before the first classification attempt of a chunk.
+const STREAM_WINDOW_BYTES: usize = 64 * 1024;
+
+/// Structural classification driven by repeated reads from an [`io::Read`] source, rather than
+/// requiring the whole document in memory up front.
+pub struct StreamingClassifier<R> {
+    reader: R,
+    /// Bytes read but not yet known to be outside of a quoted string; reused across calls to
+    /// [`StreamingClassifier::next_chunk`], cleared once a safe point to discard it is found.
+    buffer: Vec<u8>,
+    /// Document-global offset of `buffer[0]`.
+    consumed: usize,
+    eof: bool,
+}
+
+impl<R: io::Read> StreamingClassifier<R> {
+    /// Create a new streaming classifier reading structural characters out of `reader`.
+    #[must_use]
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: Vec::new(),
+            consumed: 0,
+            eof: false,
+        }
+    }
+
+    /// Read as much of the stream as is needed to classify the next chunk, and return every
+    /// [`Structural`] value found in it, with indices relative to the start of the whole stream.
+    ///
+    /// Returns an empty `Vec` once the stream is exhausted. A quoted string spanning several reads
+    /// is read to its end within the same call, so no results are held back for a later call.
+    ///
+    /// # Errors
+    /// Propagates any [`io::Error`] returned by the underlying reader.
+    pub fn next_chunk(&mut self) -> io::Result<Vec<Structural>> {
+        let mut read_buf = [0_u8; 8192];
+
+        while !self.eof && (self.buffer.len() < STREAM_WINDOW_BYTES || ends_inside_string(&self.buffer)) {
+            let n = self.reader.read(&mut read_buf)?;
+            if n == 0 {
+                self.eof = true;
+                break;
+            }
+            self.buffer.extend_from_slice(&read_buf[..n]);
+        }
+
+        if self.buffer.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let aligned = AlignedBytes::<Twice<BlockAlignment>>::new_padded(&self.buffer);
+        let quotes = classify_quoted_sequences(&aligned);
+        let classifier = classify_structural_characters(quotes);
+        let results = classifier.map(|s| s.offset(self.consumed)).collect();
+
+        if self.eof || !ends_inside_string(&self.buffer) {
+            self.consumed += self.buffer.len();
+            self.buffer.clear();
+        }
+
+        Ok(results)
+    }
+}
+
+/// Conservative scan for whether `bytes` ends inside a quoted string, tracking the same
+/// unescaped-quote parity the SIMD quote classifiers compute, but scalar and over the whole
+/// buffer: cheap enough here since it only runs once per refill, not once per block.
+///
+/// A trailing run of backslashes of odd length means the very last byte is itself an unescaped
+/// escape character, pending completion by whatever byte the next read brings in.
+fn ends_inside_string(bytes: &[u8]) -> bool {
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for &b in bytes {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match b {
+            b'\\' if in_string => escaped = true,
+            b'"' => in_string = !in_string,
+            _ => (),
+        }
+    }
+
+    in_string
+}
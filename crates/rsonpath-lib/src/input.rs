@@ -11,20 +11,34 @@
 //! | file based     | [`MmapInput`]  |
 //! | memory based | [`BorrowedBytes`] |
 //! | [`Read`](std::io::Read) based | [`BufferedInput`] |
+//! | [`Read`](std::io::Read) + [`Seek`](std::io::Seek) based, too large to buffer | [`PagedInput`] |
+//! | gzip/zstd/brotli-compressed | [`CompressedInput`] |
 //!
 pub mod borrowed;
+#[cfg(feature = "std")]
 pub mod buffered;
+#[cfg(feature = "std")]
+pub mod compressed;
 pub mod error;
+#[cfg(feature = "std")]
 pub mod mmap;
 mod padding;
+#[cfg(feature = "std")]
+pub mod paged;
 mod slice;
 pub use borrowed::BorrowedBytes;
+#[cfg(feature = "std")]
 pub use buffered::BufferedInput;
+#[cfg(feature = "std")]
+pub use compressed::{Codec, CompressedInput};
+#[cfg(feature = "std")]
 pub use mmap::MmapInput;
+#[cfg(feature = "std")]
+pub use paged::PagedInput;
 
 use self::error::InputError;
 use crate::{query::JsonString, result::InputRecorder, FallibleIterator};
-use std::ops::Deref;
+use core::ops::Deref;
 
 /// Make the struct repr(C) with alignment equal to [`MAX_BLOCK_SIZE`].
 macro_rules! repr_align_block_size {
@@ -183,15 +197,3 @@ impl<'i, const N: usize> InputBlock<'i, N> for &'i [u8] {
         (&self[..N / 2], &self[N / 2..])
     }
 }
-
-pub(super) trait SliceSeekable {
-    fn is_member_match(&self, from: usize, to: usize, member: &JsonString) -> bool;
-
-    fn seek_backward(&self, from: usize, needle: u8) -> Option<usize>;
-
-    fn seek_forward<const N: usize>(&self, from: usize, needles: [u8; N]) -> Option<(usize, u8)>;
-
-    fn seek_non_whitespace_forward(&self, from: usize) -> Option<(usize, u8)>;
-
-    fn seek_non_whitespace_backward(&self, from: usize) -> Option<(usize, u8)>;
-}
@@ -0,0 +1,313 @@
+//! Reads an [`Input`] lazily out of an arbitrary [`Read`] source, retaining every byte seen so far.
+//!
+//! Choose this implementation if:
+//!
+//! 1. The source is not a plain file you can [`MmapInput`](`super::MmapInput`);
+//! 2. It does not support [`Seek`] either (a pipe, a socket, `stdin`), ruling out
+//!    [`PagedInput`](`super::PagedInput`), which needs to re-seek the source on a backward page
+//!    miss.
+//!
+//! ## Performance characteristics
+//!
+//! Bytes are pulled from the source in [`MAX_BLOCK_SIZE`] increments as [`iter_blocks`](Input::iter_blocks)
+//! or a seek first needs them, and appended to a backing buffer that is never shrunk or evicted
+//! from: backward seeks and member matching both need random access into data already scanned, and
+//! with no [`Seek`] on the source there is no way to page it back in later. This makes memory use
+//! proportional to how much of the document has been consumed rather than its total size, unlike
+//! [`BorrowedBytes`](`super::BorrowedBytes`), but unbounded for a sufficiently long backward scan,
+//! unlike [`PagedInput`]'s bounded window. Prefer [`PagedInput`] whenever the source can [`Seek`].
+
+use super::*;
+use crate::query::JsonString;
+use std::{
+    cell::{Cell, RefCell},
+    io::Read,
+};
+
+/// Input wrapping a [`Read`] source, buffering it in as it is read and never evicting.
+pub struct BufferedInput<R> {
+    source: RefCell<R>,
+    buffer: RefCell<Vec<u8>>,
+    /// Length of the source once it has been read to EOF; `None` until then.
+    len: Cell<Option<usize>>,
+}
+
+impl<R> BufferedInput<R>
+where
+    R: Read,
+{
+    /// Create a new [`BufferedInput`] wrapping `source`.
+    ///
+    /// Nothing is read from `source` until the first block is iterated or a seek is performed.
+    #[must_use]
+    #[inline]
+    pub fn new(source: R) -> Self {
+        Self {
+            source: RefCell::new(source),
+            buffer: RefCell::new(Vec::new()),
+            len: Cell::new(None),
+        }
+    }
+
+    /// Return the byte at `idx`, reading more of the source into the buffer if needed.
+    ///
+    /// Returns `None` if `idx` is at or past the end of the source, or if reading the source to
+    /// reach it failed; callers that need to distinguish the two and surface the error should use
+    /// [`try_byte_at`](Self::try_byte_at) instead.
+    fn byte_at(&self, idx: usize) -> Option<u8> {
+        self.try_byte_at(idx).ok().flatten()
+    }
+
+    /// Return the byte at `idx`, reading more of the source into the buffer if needed.
+    ///
+    /// Returns `Ok(None)` if `idx` is at or past the end of the source.
+    ///
+    /// # Errors
+    /// Propagates any [`InputError`] encountered while reading the source to reach `idx`.
+    fn try_byte_at(&self, idx: usize) -> Result<Option<u8>, InputError> {
+        {
+            let buffer = self.buffer.borrow();
+            if idx < buffer.len() {
+                return Ok(Some(buffer[idx]));
+            }
+            if let Some(len) = self.len.get() {
+                if idx >= len {
+                    return Ok(None);
+                }
+            }
+        }
+
+        self.fill_to(idx)?;
+        let buffer = self.buffer.borrow();
+        Ok(buffer.get(idx).copied())
+    }
+
+    /// Read from the source, appending to the buffer, until it holds at least `idx + 1` bytes or
+    /// the source is exhausted.
+    ///
+    /// # Errors
+    /// Propagates any [`InputError`] raised by the underlying source's [`Read::read`].
+    fn fill_to(&self, idx: usize) -> Result<(), InputError> {
+        let mut buffer = self.buffer.borrow_mut();
+        let mut source = self.source.borrow_mut();
+
+        while buffer.len() <= idx {
+            let mut chunk = [0; MAX_BLOCK_SIZE];
+            let mut read = 0;
+            loop {
+                match source.read(&mut chunk[read..]) {
+                    Ok(0) => break,
+                    Ok(n) => read += n,
+                    Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                    Err(err) => return Err(InputError::from(err)),
+                }
+            }
+
+            if read == 0 {
+                // Hit EOF; record the true length and pad the buffer up to a full block so callers
+                // can keep relying on the `MAX_BLOCK_SIZE` padding guarantee.
+                let len = buffer.len();
+                self.len.set(Some(len));
+                let padded_len = len.next_multiple_of(MAX_BLOCK_SIZE).max(MAX_BLOCK_SIZE);
+                buffer.resize(padded_len, b' ');
+                return Ok(());
+            }
+
+            buffer.extend_from_slice(&chunk[..read]);
+        }
+
+        Ok(())
+    }
+
+    /// Number of synthetic padding bytes appended past the real end of the source, once known.
+    fn trailing_padding(&self) -> usize {
+        match self.len.get() {
+            Some(len) => len.next_multiple_of(MAX_BLOCK_SIZE).max(MAX_BLOCK_SIZE) - len,
+            None => 0,
+        }
+    }
+}
+
+impl<R> Input for BufferedInput<R>
+where
+    R: Read,
+{
+    type BlockIterator<'i, 'r, Rec, const N: usize> = BufferedInputBlockIterator<'i, 'r, R, Rec, N>
+    where
+        Self: 'i,
+        Rec: InputRecorder<Self::Block<'i, N>> + 'r;
+
+    type Error = InputError;
+
+    type Block<'i, const N: usize> = BufferedBlock<N> where Self: 'i;
+
+    #[inline(always)]
+    fn len_hint(&self) -> Option<usize> {
+        self.len.get()
+    }
+
+    #[inline(always)]
+    fn leading_padding_len(&self) -> usize {
+        0
+    }
+
+    #[inline(always)]
+    fn trailing_padding_len(&self) -> usize {
+        self.trailing_padding()
+    }
+
+    #[inline(always)]
+    fn iter_blocks<'i, 'r, Rec, const N: usize>(&'i self, recorder: &'r Rec) -> Self::BlockIterator<'i, 'r, Rec, N>
+    where
+        Rec: InputRecorder<Self::Block<'i, N>>,
+    {
+        BufferedInputBlockIterator {
+            input: self,
+            recorder,
+            idx: 0,
+        }
+    }
+
+    #[inline]
+    fn seek_backward(&self, from: usize, needle: u8) -> Option<usize> {
+        let mut i = from;
+        loop {
+            if self.byte_at(i)? == needle {
+                return Some(i);
+            }
+            if i == 0 {
+                return None;
+            }
+            i -= 1;
+        }
+    }
+
+    #[inline]
+    fn seek_forward<const N: usize>(&self, from: usize, needles: [u8; N]) -> Result<Option<(usize, u8)>, Self::Error> {
+        let mut i = from;
+        loop {
+            match self.try_byte_at(i)? {
+                None => return Ok(None),
+                Some(byte) if needles.contains(&byte) => return Ok(Some((i, byte))),
+                Some(_) => i += 1,
+            }
+        }
+    }
+
+    #[inline]
+    fn seek_non_whitespace_forward(&self, from: usize) -> Result<Option<(usize, u8)>, Self::Error> {
+        let mut i = from;
+        loop {
+            match self.try_byte_at(i)? {
+                None => return Ok(None),
+                Some(byte) if !is_whitespace(byte) => return Ok(Some((i, byte))),
+                Some(_) => i += 1,
+            }
+        }
+    }
+
+    #[inline]
+    fn seek_non_whitespace_backward(&self, from: usize) -> Option<(usize, u8)> {
+        let mut i = from;
+        loop {
+            if let Some(byte) = self.byte_at(i) {
+                if !is_whitespace(byte) {
+                    return Some((i, byte));
+                }
+            }
+            if i == 0 {
+                return None;
+            }
+            i -= 1;
+        }
+    }
+
+    #[inline]
+    fn is_member_match(&self, from: usize, to: usize, member: &JsonString) -> bool {
+        let expected = member.bytes_with_quotes();
+        if to - from != expected.len() {
+            return false;
+        }
+        // An escaped opening quote belongs to the preceding string, not to a member name.
+        if from > 0 && self.byte_at(from - 1) == Some(b'\\') {
+            return false;
+        }
+
+        (from..to).zip(expected).all(|(i, &expected_byte)| self.byte_at(i) == Some(expected_byte))
+    }
+}
+
+#[inline(always)]
+fn is_whitespace(byte: u8) -> bool {
+    byte == b' ' || byte == b'\t' || byte == b'\n' || byte == b'\r'
+}
+
+/// Iterator over blocks of [`BufferedInput`] of size exactly `N`.
+pub struct BufferedInputBlockIterator<'i, 'r, R, Rec, const N: usize> {
+    input: &'i BufferedInput<R>,
+    recorder: &'r Rec,
+    idx: usize,
+}
+
+impl<'i, 'r, R, Rec, const N: usize> InputBlockIterator<'i, N> for BufferedInputBlockIterator<'i, 'r, R, Rec, N>
+where
+    R: Read,
+    Rec: InputRecorder<BufferedBlock<N>>,
+{
+    type Block = BufferedBlock<N>;
+    type Error = InputError;
+
+    #[inline]
+    fn next(&mut self) -> Result<Option<Self::Block>, Self::Error> {
+        let mut block = [0; N];
+        let mut any = false;
+        for (i, slot) in block.iter_mut().enumerate() {
+            match self.input.try_byte_at(self.idx + i)? {
+                Some(byte) => {
+                    *slot = byte;
+                    any = true;
+                }
+                None if any => *slot = b' ',
+                None => return Ok(None),
+            }
+        }
+
+        self.idx += N;
+        let block = BufferedBlock(block);
+        self.recorder.record_block_end(block);
+        Ok(Some(block))
+    }
+
+    #[inline(always)]
+    fn get_offset(&self) -> usize {
+        self.idx
+    }
+
+    #[inline(always)]
+    fn offset(&mut self, count: isize) {
+        assert!(count >= 0);
+        self.idx += count as usize * N;
+    }
+}
+
+/// A block of bytes of size `N`, owned, since [`BufferedInput`]'s backing buffer lives behind a
+/// [`RefCell`] that can grow (and reallocate) on the very next read.
+#[derive(Debug, Clone, Copy)]
+pub struct BufferedBlock<const N: usize>([u8; N]);
+
+impl<const N: usize> std::ops::Deref for BufferedBlock<N> {
+    type Target = [u8];
+
+    #[inline(always)]
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<'i, const N: usize> InputBlock<'i, N> for BufferedBlock<N> {
+    #[inline(always)]
+    fn halves(&self) -> (&[u8], &[u8]) {
+        assert_eq!(N % 2, 0);
+        (&self.0[..N / 2], &self.0[N / 2..])
+    }
+}
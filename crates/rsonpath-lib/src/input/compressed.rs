@@ -0,0 +1,199 @@
+//! Transparent gzip/zstd/brotli decompression layered on top of [`BufferedInput`].
+//!
+//! Choose this implementation if the source is a compressed JSON dump (`.json.gz`, `.json.zst`,
+//! ...) rather than plain JSON: a very common shape for large logs and data exports. Decompression
+//! happens on the fly, feeding the uncompressed byte stream straight into a [`BufferedInput`] the
+//! same way a plain [`Read`] source would, so the block iterator, padding, and backward-seek
+//! behaviour are all inherited from it unchanged; only the bytes handed to the block iterator have
+//! already been inflated.
+//!
+//! Each codec is gated behind its own Cargo feature (`gzip`, `zstd`, `brotli`) so a build that only
+//! ever sees one compression scheme doesn't pull in decoders for the others.
+
+use super::buffered::BufferedInput;
+use super::error::InputError;
+use super::*;
+use crate::query::JsonString;
+use std::io::{self, BufRead, Read};
+
+/// Compression codec used by [`CompressedInput`], either pinned explicitly or detected from the
+/// source's magic bytes via [`Codec::sniff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Codec {
+    /// `.gz` / [RFC 1952](https://www.rfc-editor.org/rfc/rfc1952) gzip.
+    #[cfg(feature = "gzip")]
+    Gzip,
+    /// `.zst` Zstandard.
+    #[cfg(feature = "zstd")]
+    Zstd,
+    /// `.br` Brotli.
+    #[cfg(feature = "brotli")]
+    Brotli,
+}
+
+impl Codec {
+    /// Identify the codec from a stream's leading bytes, without consuming them.
+    ///
+    /// Returns `None` if the magic bytes don't match any codec compiled in; brotli has no magic
+    /// number, so it is never detected this way and must be requested explicitly.
+    #[must_use]
+    pub fn sniff(magic: &[u8]) -> Option<Self> {
+        #[cfg(feature = "gzip")]
+        if magic.starts_with(&[0x1f, 0x8b]) {
+            return Some(Self::Gzip);
+        }
+        #[cfg(feature = "zstd")]
+        if magic.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            return Some(Self::Zstd);
+        }
+        #[cfg_attr(not(any(feature = "gzip", feature = "zstd")), allow(unused_variables))]
+        None
+    }
+}
+
+/// Input decompressing a gzip/zstd/brotli-compressed [`Read`] source on the fly.
+///
+/// A thin wrapper around [`BufferedInput`]: all seek, padding, and block-iteration behaviour comes
+/// from the inner [`BufferedInput`], the same as it would for any other [`Read`] source, since from
+/// its perspective the only difference is that the bytes it reads have already been inflated.
+pub struct CompressedInput<R: Read> {
+    inner: BufferedInput<Decoder<R>>,
+}
+
+impl<R> CompressedInput<R>
+where
+    R: Read,
+{
+    /// Wrap `source`, decompressing it with the given, explicitly chosen `codec`.
+    #[must_use]
+    #[inline]
+    pub fn new(source: R, codec: Codec) -> Self {
+        Self {
+            inner: BufferedInput::new(Decoder::new(source, codec)),
+        }
+    }
+}
+
+impl<R> CompressedInput<R>
+where
+    R: BufRead,
+{
+    /// Wrap `source`, sniffing its codec from the leading magic bytes.
+    ///
+    /// # Errors
+    /// Returns [`InputError`] if the source could not be read far enough to sniff, or if its magic
+    /// bytes don't match any codec compiled in.
+    #[inline]
+    pub fn sniff(mut source: R) -> Result<Self, InputError> {
+        let magic = source.fill_buf().map_err(InputError::from)?;
+        let codec = Codec::sniff(magic).ok_or(InputError::InvalidFormat)?;
+        Ok(Self::new(source, codec))
+    }
+}
+
+impl<R> Input for CompressedInput<R>
+where
+    R: Read,
+{
+    type BlockIterator<'i, 'r, Rec, const N: usize> = <BufferedInput<Decoder<R>> as Input>::BlockIterator<'i, 'r, Rec, N>
+    where
+        Self: 'i,
+        Rec: InputRecorder<Self::Block<'i, N>> + 'r;
+
+    type Error = InputError;
+
+    type Block<'i, const N: usize> = <BufferedInput<Decoder<R>> as Input>::Block<'i, N> where Self: 'i;
+
+    #[inline(always)]
+    fn len_hint(&self) -> Option<usize> {
+        // The compressed source's length has no relation to the inflated length, and the inner
+        // `BufferedInput` only learns its own length at EOF, same as any other `Read` source.
+        self.inner.len_hint()
+    }
+
+    #[inline(always)]
+    fn leading_padding_len(&self) -> usize {
+        self.inner.leading_padding_len()
+    }
+
+    #[inline(always)]
+    fn trailing_padding_len(&self) -> usize {
+        self.inner.trailing_padding_len()
+    }
+
+    #[inline(always)]
+    fn iter_blocks<'i, 'r, Rec, const N: usize>(&'i self, recorder: &'r Rec) -> Self::BlockIterator<'i, 'r, Rec, N>
+    where
+        Rec: InputRecorder<Self::Block<'i, N>>,
+    {
+        self.inner.iter_blocks(recorder)
+    }
+
+    #[inline]
+    fn seek_backward(&self, from: usize, needle: u8) -> Option<usize> {
+        self.inner.seek_backward(from, needle)
+    }
+
+    #[inline]
+    fn seek_forward<const N: usize>(&self, from: usize, needles: [u8; N]) -> Result<Option<(usize, u8)>, Self::Error> {
+        self.inner.seek_forward(from, needles)
+    }
+
+    #[inline]
+    fn seek_non_whitespace_forward(&self, from: usize) -> Result<Option<(usize, u8)>, Self::Error> {
+        self.inner.seek_non_whitespace_forward(from)
+    }
+
+    #[inline]
+    fn seek_non_whitespace_backward(&self, from: usize) -> Option<(usize, u8)> {
+        self.inner.seek_non_whitespace_backward(from)
+    }
+
+    #[inline]
+    fn is_member_match(&self, from: usize, to: usize, member: &JsonString) -> bool {
+        self.inner.is_member_match(from, to, member)
+    }
+}
+
+/// Dispatches [`Read`] to whichever codec's decoder [`CompressedInput`] was built with.
+enum Decoder<R: Read> {
+    #[cfg(feature = "gzip")]
+    Gzip(flate2::read::GzDecoder<R>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::stream::read::Decoder<'static, io::BufReader<R>>),
+    #[cfg(feature = "brotli")]
+    Brotli(brotli::Decompressor<R>),
+}
+
+impl<R> Decoder<R>
+where
+    R: Read,
+{
+    fn new(source: R, codec: Codec) -> Self {
+        match codec {
+            #[cfg(feature = "gzip")]
+            Codec::Gzip => Self::Gzip(flate2::read::GzDecoder::new(source)),
+            #[cfg(feature = "zstd")]
+            Codec::Zstd => Self::Zstd(zstd::stream::read::Decoder::new(source).expect("zstd decoder init failed")),
+            #[cfg(feature = "brotli")]
+            Codec::Brotli => Self::Brotli(brotli::Decompressor::new(source, MAX_BLOCK_SIZE)),
+        }
+    }
+}
+
+impl<R> Read for Decoder<R>
+where
+    R: Read,
+{
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            #[cfg(feature = "gzip")]
+            Self::Gzip(decoder) => decoder.read(buf),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(decoder) => decoder.read(buf),
+            #[cfg(feature = "brotli")]
+            Self::Brotli(decoder) => decoder.read(buf),
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! Error type shared by every fallible [`Input`](super::Input) operation.
+
+use core::fmt::{self, Display};
+
+/// Error encountered while reading, seeking, or recognizing an [`Input`](super::Input) source.
+#[derive(Debug)]
+pub enum InputError {
+    /// The underlying source reported an I/O error while being read.
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+    /// The source's contents could not be recognized as a supported format.
+    InvalidFormat,
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            Self::Io(err) => write!(f, "reading from the input source failed: {err}"),
+            Self::InvalidFormat => write!(f, "the input source is not in a recognized format"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::InvalidFormat => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for InputError {
+    #[inline(always)]
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
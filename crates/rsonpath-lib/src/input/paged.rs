@@ -0,0 +1,340 @@
+//! Reads an [`Input`] lazily out of a [`Read`] + [`Seek`] source.
+//!
+//! Choose this implementation if:
+//!
+//! 1. The source is not a plain file you can [`MmapInput`](`super::MmapInput`), but does support
+//!    [`Seek`] (an open file handle on a platform without `mmap`, a compressed stream wrapper,
+//!    ...);
+//! 2. The document may be too large to buffer wholesale the way
+//!    [`BufferedInput`](`super::BufferedInput`) does.
+//!
+//! ## Performance characteristics
+//!
+//! Bytes are paged in [`WINDOW_SIZE`]-aligned windows, a multiple of [`MAX_BLOCK_SIZE`], as they
+//! are first read. A backward seek that falls outside the currently loaded window re-seeks the
+//! underlying source and pages in the window that contains it, rather than keeping every
+//! previously seen byte resident the way `BufferedInput` does. This trades some repeated reads of
+//! the source for bounded memory use, a middle ground between `MmapInput` and `BufferedInput`.
+
+use super::*;
+use crate::query::JsonString;
+use std::{
+    cell::{Cell, RefCell},
+    io::{Read, Seek, SeekFrom},
+};
+
+/// Number of [`MAX_BLOCK_SIZE`]-sized blocks paged in from the source at a time.
+const WINDOW_BLOCKS: usize = 32;
+
+/// Size, in bytes, of a single paged-in window. Always a multiple of [`MAX_BLOCK_SIZE`].
+const WINDOW_SIZE: usize = WINDOW_BLOCKS * MAX_BLOCK_SIZE;
+
+/// Input wrapping a [`Read`] + [`Seek`] source, paging it in on demand.
+pub struct PagedInput<R> {
+    source: RefCell<R>,
+    window: RefCell<Window>,
+    /// Length of the source once it has been read to EOF at least once; `None` until then.
+    len: Cell<Option<usize>>,
+}
+
+/// The currently paged-in window: `WINDOW_SIZE` bytes (or fewer, at EOF) starting at `start`.
+struct Window {
+    start: usize,
+    bytes: Vec<u8>,
+}
+
+impl Window {
+    fn empty() -> Self {
+        Self {
+            start: 0,
+            bytes: Vec::new(),
+        }
+    }
+
+    fn contains(&self, idx: usize) -> bool {
+        idx >= self.start && idx < self.start + self.bytes.len()
+    }
+}
+
+impl<R> PagedInput<R>
+where
+    R: Read + Seek,
+{
+    /// Create a new [`PagedInput`] wrapping `source`.
+    ///
+    /// Nothing is read from `source` until the first block is iterated or a seek is performed.
+    #[must_use]
+    #[inline]
+    pub fn new(source: R) -> Self {
+        Self {
+            source: RefCell::new(source),
+            window: RefCell::new(Window::empty()),
+            len: Cell::new(None),
+        }
+    }
+
+    /// Load the window that contains `idx`, paging it in from the source if it is not already
+    /// the currently loaded window.
+    ///
+    /// Returns the byte at `idx`, or `None` if `idx` is at or past the end of the source, or if
+    /// paging in the window that would contain it failed; callers that need to distinguish the
+    /// two and surface the error should use [`try_byte_at`](Self::try_byte_at) instead.
+    fn byte_at(&self, idx: usize) -> Option<u8> {
+        self.try_byte_at(idx).ok().flatten()
+    }
+
+    /// Load the window that contains `idx`, paging it in from the source if it is not already
+    /// the currently loaded window.
+    ///
+    /// Returns `Ok(None)` if `idx` is at or past the end of the source.
+    ///
+    /// # Errors
+    /// Propagates any [`InputError`] encountered while paging in the window.
+    fn try_byte_at(&self, idx: usize) -> Result<Option<u8>, InputError> {
+        {
+            let window = self.window.borrow();
+            if window.contains(idx) {
+                return Ok(Some(window.bytes[idx - window.start]));
+            }
+            if let Some(len) = self.len.get() {
+                if idx >= len {
+                    return Ok(None);
+                }
+            }
+        }
+
+        self.load_window_containing(idx)?;
+        let window = self.window.borrow();
+        Ok(window.bytes.get(idx - window.start).copied())
+    }
+
+    /// # Errors
+    /// Propagates any [`InputError`] raised by the underlying source's [`Read::read`].
+    fn load_window_containing(&self, idx: usize) -> Result<(), InputError> {
+        let aligned_start = (idx / WINDOW_SIZE) * WINDOW_SIZE;
+        let mut source = self.source.borrow_mut();
+        // A source that can't seek to an in-bounds offset has a bug in its `Seek` impl; there is
+        // no sensible way to recover a window in that case.
+        source
+            .seek(SeekFrom::Start(aligned_start as u64))
+            .expect("seeking the input source must succeed");
+
+        let mut bytes = vec![0; WINDOW_SIZE];
+        let mut read = 0;
+        loop {
+            match source.read(&mut bytes[read..]) {
+                Ok(0) => break,
+                Ok(n) => read += n,
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(InputError::from(err)),
+            }
+        }
+        bytes.truncate(read);
+
+        if read < WINDOW_SIZE {
+            // Hit EOF while filling this window; record the true length and pad the window up to
+            // a full block so callers can keep relying on the `MAX_BLOCK_SIZE` padding guarantee.
+            self.len.set(Some(aligned_start + read));
+            let padded_len = read.next_multiple_of(MAX_BLOCK_SIZE).max(MAX_BLOCK_SIZE);
+            bytes.resize(padded_len, b' ');
+        }
+
+        *self.window.borrow_mut() = Window {
+            start: aligned_start,
+            bytes,
+        };
+
+        Ok(())
+    }
+
+    /// Number of synthetic padding bytes appended past the real end of the source, once known.
+    fn trailing_padding(&self) -> usize {
+        match self.len.get() {
+            Some(len) => len.next_multiple_of(MAX_BLOCK_SIZE).max(MAX_BLOCK_SIZE) - len,
+            None => 0,
+        }
+    }
+}
+
+impl<R> Input for PagedInput<R>
+where
+    R: Read + Seek,
+{
+    type BlockIterator<'i, 'r, Rec, const N: usize> = PagedInputBlockIterator<'i, 'r, R, Rec, N>
+    where
+        Self: 'i,
+        Rec: InputRecorder<Self::Block<'i, N>> + 'r;
+
+    type Error = InputError;
+
+    type Block<'i, const N: usize> = PagedBlock<N> where Self: 'i;
+
+    #[inline(always)]
+    fn len_hint(&self) -> Option<usize> {
+        self.len.get()
+    }
+
+    #[inline(always)]
+    fn leading_padding_len(&self) -> usize {
+        0
+    }
+
+    #[inline(always)]
+    fn trailing_padding_len(&self) -> usize {
+        self.trailing_padding()
+    }
+
+    #[inline(always)]
+    fn iter_blocks<'i, 'r, Rec, const N: usize>(&'i self, recorder: &'r Rec) -> Self::BlockIterator<'i, 'r, Rec, N>
+    where
+        Rec: InputRecorder<Self::Block<'i, N>>,
+    {
+        PagedInputBlockIterator {
+            input: self,
+            recorder,
+            idx: 0,
+        }
+    }
+
+    #[inline]
+    fn seek_backward(&self, from: usize, needle: u8) -> Option<usize> {
+        let mut i = from;
+        loop {
+            if self.byte_at(i)? == needle {
+                return Some(i);
+            }
+            if i == 0 {
+                return None;
+            }
+            i -= 1;
+        }
+    }
+
+    #[inline]
+    fn seek_forward<const N: usize>(&self, from: usize, needles: [u8; N]) -> Result<Option<(usize, u8)>, Self::Error> {
+        let mut i = from;
+        loop {
+            match self.try_byte_at(i)? {
+                None => return Ok(None),
+                Some(byte) if needles.contains(&byte) => return Ok(Some((i, byte))),
+                Some(_) => i += 1,
+            }
+        }
+    }
+
+    #[inline]
+    fn seek_non_whitespace_forward(&self, from: usize) -> Result<Option<(usize, u8)>, Self::Error> {
+        let mut i = from;
+        loop {
+            match self.try_byte_at(i)? {
+                None => return Ok(None),
+                Some(byte) if !is_whitespace(byte) => return Ok(Some((i, byte))),
+                Some(_) => i += 1,
+            }
+        }
+    }
+
+    #[inline]
+    fn seek_non_whitespace_backward(&self, from: usize) -> Option<(usize, u8)> {
+        let mut i = from;
+        loop {
+            if let Some(byte) = self.byte_at(i) {
+                if !is_whitespace(byte) {
+                    return Some((i, byte));
+                }
+            }
+            if i == 0 {
+                return None;
+            }
+            i -= 1;
+        }
+    }
+
+    #[inline]
+    fn is_member_match(&self, from: usize, to: usize, member: &JsonString) -> bool {
+        let expected = member.bytes_with_quotes();
+        if to - from != expected.len() {
+            return false;
+        }
+        // An escaped opening quote belongs to the preceding string, not to a member name.
+        if from > 0 && self.byte_at(from - 1) == Some(b'\\') {
+            return false;
+        }
+
+        (from..to).zip(expected).all(|(i, &expected_byte)| self.byte_at(i) == Some(expected_byte))
+    }
+}
+
+#[inline(always)]
+fn is_whitespace(byte: u8) -> bool {
+    byte == b' ' || byte == b'\t' || byte == b'\n' || byte == b'\r'
+}
+
+/// Iterator over blocks of [`PagedInput`] of size exactly `N`.
+pub struct PagedInputBlockIterator<'i, 'r, R, Rec, const N: usize> {
+    input: &'i PagedInput<R>,
+    recorder: &'r Rec,
+    idx: usize,
+}
+
+impl<'i, 'r, R, Rec, const N: usize> InputBlockIterator<'i, N> for PagedInputBlockIterator<'i, 'r, R, Rec, N>
+where
+    R: Read + Seek,
+    Rec: InputRecorder<PagedBlock<N>>,
+{
+    type Block = PagedBlock<N>;
+    type Error = InputError;
+
+    #[inline]
+    fn next(&mut self) -> Result<Option<Self::Block>, Self::Error> {
+        let mut block = [0; N];
+        let mut any = false;
+        for (i, slot) in block.iter_mut().enumerate() {
+            match self.input.try_byte_at(self.idx + i)? {
+                Some(byte) => {
+                    *slot = byte;
+                    any = true;
+                }
+                None if any => *slot = b' ',
+                None => return Ok(None),
+            }
+        }
+
+        self.idx += N;
+        let block = PagedBlock(block);
+        self.recorder.record_block_end(block);
+        Ok(Some(block))
+    }
+
+    #[inline(always)]
+    fn get_offset(&self) -> usize {
+        self.idx
+    }
+
+    #[inline(always)]
+    fn offset(&mut self, count: isize) {
+        assert!(count >= 0);
+        self.idx += count as usize * N;
+    }
+}
+
+/// A block of bytes of size `N`, owned, since [`PagedInput`] has nothing resident to borrow from.
+#[derive(Debug, Clone, Copy)]
+pub struct PagedBlock<const N: usize>([u8; N]);
+
+impl<const N: usize> std::ops::Deref for PagedBlock<N> {
+    type Target = [u8];
+
+    #[inline(always)]
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<'i, const N: usize> InputBlock<'i, N> for PagedBlock<N> {
+    #[inline(always)]
+    fn halves(&self) -> (&[u8], &[u8]) {
+        assert_eq!(N % 2, 0);
+        (&self.0[..N / 2], &self.0[N / 2..])
+    }
+}
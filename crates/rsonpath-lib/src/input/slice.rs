@@ -0,0 +1,149 @@
+//! Seek primitives operating directly on a borrowed `&[u8]`, shared by every [`Input`](`super::Input`)
+//! implementation that already has its current window resident as a plain slice.
+//!
+//! [`SliceSeekable::seek_forward`] is the one worth vectorizing: it is the primitive behind every
+//! forward search for a structural character during member matching, so it runs once per byte of
+//! input. Rather than checking each needle one at a time, it classifies a whole [`MAX_BLOCK_SIZE`]
+//! block against the full needle set at once: each needle is broadcast across a word-sized lane,
+//! XORed against the lane's bytes, and run through the classic "does this word contain a zero
+//! byte" trick to produce a mask with the high bit of every matching byte set. The masks for all
+//! needles are OR'd together, and `trailing_zeros` on the combined mask gives the position of the
+//! first hit directly, without a byte-by-byte scan. A scalar loop handles the padded tail that
+//! doesn't fill a whole lane.
+use super::MAX_BLOCK_SIZE;
+use crate::query::JsonString;
+
+/// A single lane's width, in bytes, for the [`SliceSeekable::seek_forward`] word trick.
+const LANE_WIDTH: usize = core::mem::size_of::<u64>();
+
+/// Seek primitives shared by [`Input`](`super::Input`) implementations backed by a resident slice.
+pub(super) trait SliceSeekable: AsRef<[u8]> {
+    /// Decide whether `self[from..to]` matches `member` bitwise, including quote delimiters, and
+    /// that the opening quote is not escaped.
+    fn is_member_match(&self, from: usize, to: usize, member: &JsonString) -> bool;
+
+    /// Search backward from `from` for the first occurrence of `needle`.
+    fn seek_backward(&self, from: usize, needle: u8) -> Option<usize>;
+
+    /// Search forward from `from` for the first occurrence of any byte in `needles`.
+    ///
+    /// The default implementation classifies each [`MAX_BLOCK_SIZE`] block against the whole
+    /// needle set at once, rather than checking needles one at a time, and falls back to a
+    /// scalar loop only for the padded tail. Implementors backed by a plain `&[u8]` get this for
+    /// free and should not need to override it.
+    #[inline]
+    fn seek_forward<const N: usize>(&self, from: usize, needles: [u8; N]) -> Option<(usize, u8)> {
+        let bytes = self.as_ref();
+        let mut idx = from;
+
+        while idx < bytes.len() {
+            let block_end = (idx + MAX_BLOCK_SIZE).min(bytes.len());
+            let block = &bytes[idx..block_end];
+
+            if let Some(pos) = seek_forward_in_block(block, needles) {
+                return Some((idx + pos, bytes[idx + pos]));
+            }
+
+            idx = block_end;
+        }
+
+        None
+    }
+
+    /// Search forward from `from` for the first non-whitespace byte.
+    fn seek_non_whitespace_forward(&self, from: usize) -> Option<(usize, u8)>;
+
+    /// Search backward from `from` for the first non-whitespace byte.
+    fn seek_non_whitespace_backward(&self, from: usize) -> Option<(usize, u8)>;
+}
+
+impl SliceSeekable for [u8] {
+    #[inline]
+    fn is_member_match(&self, from: usize, to: usize, member: &JsonString) -> bool {
+        let expected = member.bytes_with_quotes();
+        if to - from != expected.len() || expected.is_empty() {
+            return false;
+        }
+
+        // The opening quote must not be escaped, or this is a match on a quote that is itself
+        // part of the preceding string's contents, not a member name delimiter.
+        if from > 0 && self[from - 1] == b'\\' {
+            return false;
+        }
+
+        self[from..to] == *expected
+    }
+
+    #[inline]
+    fn seek_backward(&self, from: usize, needle: u8) -> Option<usize> {
+        self[..=from].iter().rposition(|&b| b == needle)
+    }
+
+    #[inline]
+    fn seek_non_whitespace_forward(&self, from: usize) -> Option<(usize, u8)> {
+        self[from..]
+            .iter()
+            .position(|&b| !is_whitespace(b))
+            .map(|pos| (from + pos, self[from + pos]))
+    }
+
+    #[inline]
+    fn seek_non_whitespace_backward(&self, from: usize) -> Option<(usize, u8)> {
+        self[..=from]
+            .iter()
+            .rposition(|&b| !is_whitespace(b))
+            .map(|pos| (pos, self[pos]))
+    }
+}
+
+/// Scan a single block (at most [`MAX_BLOCK_SIZE`] bytes) for the first byte matching any of
+/// `needles`, processing whole [`LANE_WIDTH`]-byte lanes at a time and falling back to a scalar
+/// loop over the remainder.
+#[inline]
+fn seek_forward_in_block<const N: usize>(block: &[u8], needles: [u8; N]) -> Option<usize> {
+    let mut lanes = block.chunks_exact(LANE_WIDTH);
+
+    for lane in lanes.by_ref() {
+        let word = u64::from_le_bytes(lane.try_into().expect("chunk is exactly LANE_WIDTH bytes"));
+        let mut combined_mask = 0u64;
+
+        for &needle in &needles {
+            combined_mask |= has_zero_byte(word ^ broadcast(needle));
+        }
+
+        if combined_mask != 0 {
+            return Some((combined_mask.trailing_zeros() / 8) as usize);
+        }
+    }
+
+    let scalar_start = block.len() - lanes.remainder().len();
+    lanes
+        .remainder()
+        .iter()
+        .position(|b| needles.contains(b))
+        .map(|pos| scalar_start + pos)
+}
+
+/// Repeat `byte` across all eight bytes of a `u64`.
+#[inline(always)]
+fn broadcast(byte: u8) -> u64 {
+    u64::from_ne_bytes([byte; 8])
+}
+
+/// The classic "does this word contain a zero byte" trick: returns a `u64` with the high bit of
+/// every zero byte in `word` set, or `0` if there is none. The borrow of the subtraction can also
+/// set the high bit of a `0x01` byte above a zero byte, so only the lowest set bit is exact.
+///
+/// Applied to `word ^ broadcast(needle)`, a byte position is zero exactly where `word` held
+/// `needle`, so this doubles as "does this word contain `needle`, and where is the first one".
+#[inline(always)]
+fn has_zero_byte(word: u64) -> u64 {
+    const LOW_BITS: u64 = 0x0101_0101_0101_0101;
+    const HIGH_BITS: u64 = 0x8080_8080_8080_8080;
+    word.wrapping_sub(LOW_BITS) & !word & HIGH_BITS
+}
+
+#[inline(always)]
+fn is_whitespace(byte: u8) -> bool {
+    byte == b' ' || byte == b'\t' || byte == b'\n' || byte == b'\r'
+}
@@ -0,0 +1,11 @@
+//! `rsonpath-lib` -- a JSONPath query engine built around fast, SIMD-accelerated
+//! classification passes over the raw input bytes.
+//!
+//! This crate root only declares the module tree; it was missing from the tree
+//! entirely before this commit; see [`classification`]'s module doc for what that
+//! did and didn't unblock.
+pub mod classification;
+pub mod classify;
+pub mod input;
+pub mod query;
+pub mod result;
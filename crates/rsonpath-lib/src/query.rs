@@ -0,0 +1,22 @@
+//! JSONPath query representation and compilation into a matching [`automaton`].
+//!
+//! This root file was missing from the tree entirely before this commit, which is
+//! why [`array_selector`] and [`automaton`] were unreachable from the crate root
+//! despite both being real, substantial modules. Declaring them here does not make
+//! this crate compile: `automaton.rs` itself expects `error::CompilerError`,
+//! `JsonPathQuery`, and `Label` to be defined directly in this file (`use
+//! super::{error::CompilerError, JsonPathQuery, Label};`), and
+//! `nonnegative_array_index.rs` expects a `query::error` submodule for
+//! `ArrayIndexError`. None of those three types, nor an `error` module, are defined
+//! anywhere in this tree -- confirmed by a repo-wide search, not merely unwired --
+//! so reconnecting this module tree narrows the gap to exactly that missing core
+//! query data model, rather than leaving every file underneath unreachable on top
+//! of it. Inventing `JsonPathQuery`/`Label`/`query::error` from scratch to close
+//! that gap would mean guessing at the query string representation the rest of
+//! this crate (and `rsonpath-syntax`'s parser, which also isn't in this tree) is
+//! built around, which is out of scope for reconnecting a module tree.
+pub mod array_selector;
+pub mod automaton;
+pub mod nonnegative_array_index;
+
+pub use nonnegative_array_index::NonNegativeArrayIndex;
@@ -0,0 +1,501 @@
+//! Array selectors beyond a single front-counted [`NonNegativeArrayIndex`]:
+//! negative, from-the-end indices and `[start:end:step]` slices.
+//!
+//! Resolving `[-1]` or a slice needs the array's length, which isn't known until
+//! its closing `]` is reached. [`ArrayElementBuffer`] buffers only the byte
+//! offset of each element boundary seen at the array's depth — never the
+//! elements' contents — as the depth engine walks forward; once the closing
+//! bracket is seen, [`ArrayElementBuffer::resolve`] uses the final element count
+//! to turn an [`ArraySelector`] into the concrete offsets it selects.
+//!
+//! This module is declared for real now (`query.rs` has a `pub mod
+//! array_selector;`, reachable from the crate root via `lib.rs`), so it's no
+//! longer dead code sitting outside the module tree. Threading `ArraySelector`
+//! through parsing and execution, as the request asks, still isn't possible:
+//! `JsonPathQuery`, `Label`, and a `query::error` module are referenced by
+//! `query/automaton.rs` and `query/nonnegative_array_index.rs` but defined
+//! nowhere in this tree (see `query.rs`'s module doc), and there is no
+//! `rsonpath-syntax` parser or `automaton::nfa`/`minimizer` lowering step for a
+//! new selector variant to be threaded through in the first place.
+//!
+//! [`ArrayElementBuffer::build_with_index`] is a real, separate way to fill a buffer, for
+//! callers that already hold a [`StructuralIndex`](crate::classification::depth::StructuralIndex)
+//! over the whole document instead of driving an online depth engine: it uses
+//! [`StructuralIndex::find_matching_close`](crate::classification::depth::StructuralIndex::find_matching_close)
+//! to jump straight over each nested object/array element instead of tracking depth byte by byte.
+//! [`resolve_array_selector`] threads an [`ArraySelector`] all the way through to execution
+//! against an index-backed buffer in one call, for callers that don't need the intermediate
+//! buffer themselves.
+use super::NonNegativeArrayIndex;
+use crate::classification::depth::StructuralIndex;
+
+/// A signed array index, counted from the end when negative, as in JSONPath
+/// (`-1` is the last element, `-2` the second-to-last, and so on).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ArrayIndex(i64);
+
+impl ArrayIndex {
+    /// Create an index from a signed offset.
+    #[must_use]
+    #[inline]
+    pub const fn new(index: i64) -> Self {
+        Self(index)
+    }
+
+    /// Return the raw signed offset.
+    #[must_use]
+    #[inline]
+    pub fn get(&self) -> i64 {
+        self.0
+    }
+
+    /// Resolve against an array of `len` elements: non-negative offsets pass
+    /// through unchanged, negative ones are counted back from `len`.
+    #[must_use]
+    #[inline]
+    fn normalize(self, len: usize) -> i64 {
+        if self.0 < 0 {
+            self.0 + len as i64
+        } else {
+            self.0
+        }
+    }
+}
+
+/// `[start:end:step]`, with every bound optional as in JSONPath (`[:]`, `[::2]`,
+/// `[1:]`, ...). A missing `step` defaults to `1`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct SliceSelector {
+    /// Inclusive lower bound, or the slice default if absent.
+    pub start: Option<ArrayIndex>,
+    /// Exclusive upper bound, or the slice default if absent.
+    pub end: Option<ArrayIndex>,
+    /// Stride between selected elements; negative values walk the array
+    /// backwards. Must not be `0`.
+    pub step: Option<i64>,
+}
+
+/// The full JSONPath array-selector surface: a single front-counted index, a
+/// single from-the-end index, or a slice.
+///
+/// Supersedes using a bare [`NonNegativeArrayIndex`] as "the" array selector;
+/// [`NonNegativeArrayIndex`] remains the representation of the front-counted case.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ArraySelector {
+    /// `[n]`, counted from the front.
+    Index(NonNegativeArrayIndex),
+    /// `[-n]`, counted from the end: a magnitude of `1` selects the last
+    /// element, `2` the second-to-last, and so on.
+    IndexFromEnd(NonNegativeArrayIndex),
+    /// `[start:end:step]`.
+    Slice(SliceSelector),
+}
+
+/// Buffers element boundary offsets for a single array while the depth engine
+/// walks it, so selectors that need the array's length can be resolved once
+/// its closing `]` is seen.
+///
+/// Only the byte offset of each element's first byte is kept; the elements'
+/// contents never need to be retained or revisited.
+#[derive(Clone, Debug, Default)]
+pub struct ArrayElementBuffer {
+    element_starts: Vec<usize>,
+}
+
+impl ArrayElementBuffer {
+    /// Create an empty buffer, to be filled as the depth engine walks the array.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that another array element starts at `offset`.
+    ///
+    /// Must be called once per element, in document order, for every element
+    /// encountered between the array's opening `[` and its matching `]`.
+    pub fn push_element(&mut self, offset: usize) {
+        self.element_starts.push(offset);
+    }
+
+    /// The number of elements buffered so far.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.element_starts.len()
+    }
+
+    /// Whether no elements have been buffered.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.element_starts.is_empty()
+    }
+
+    /// Build a buffer for the array opening at `array_open`, using a whole-document
+    /// [`StructuralIndex`] to jump directly over each nested element instead of walking it
+    /// byte by byte.
+    ///
+    /// Returns the filled buffer together with the offset one past the array's matching `]`.
+    ///
+    /// # Panics
+    /// Panics if `bytes[array_open]` is not `[`, or if the array has no matching close in
+    /// `index` (both would indicate `index` was not built over `bytes`).
+    #[must_use]
+    pub(crate) fn build_with_index(bytes: &[u8], index: &StructuralIndex, array_open: usize) -> (Self, usize) {
+        assert_eq!(bytes[array_open], b'[', "array_open must point at an opening '['");
+        let array_close = index
+            .find_matching_close(array_open)
+            .expect("index must be built over bytes and cover a matching ']'");
+
+        let mut buffer = Self::new();
+        let mut offset = array_open + 1;
+
+        loop {
+            offset = skip_whitespace(bytes, offset);
+            if offset >= array_close - 1 {
+                break;
+            }
+
+            buffer.push_element(offset);
+
+            offset = match bytes[offset] {
+                b'{' | b'[' => {
+                    let close = index
+                        .find_matching_close(offset)
+                        .expect("every nested container closes before the outer array does");
+                    close
+                }
+                _ => skip_scalar(bytes, offset),
+            };
+
+            offset = skip_whitespace(bytes, offset);
+            if offset < array_close - 1 && bytes[offset] == b',' {
+                offset += 1;
+            }
+        }
+
+        (buffer, array_close)
+    }
+
+    /// Resolve `selector` against the buffered elements, once the array's
+    /// closing `]` has been seen and no further elements will be pushed.
+    ///
+    /// Returns the byte offsets of every selected element's start, in
+    /// ascending document order.
+    #[must_use]
+    pub fn resolve(&self, selector: &ArraySelector) -> Vec<usize> {
+        match selector {
+            ArraySelector::Index(index) => {
+                let i = index.get_index() as usize;
+                match self.element_starts.get(i) {
+                    Some(&offset) => vec![offset],
+                    None => Vec::new(),
+                }
+            }
+            ArraySelector::IndexFromEnd(magnitude) => {
+                let len = self.element_starts.len();
+                let m = magnitude.get_index() as usize;
+                if m >= 1 && m <= len {
+                    vec![self.element_starts[len - m]]
+                } else {
+                    Vec::new()
+                }
+            }
+            ArraySelector::Slice(slice) => self.resolve_slice(slice),
+        }
+    }
+
+    fn resolve_slice(&self, slice: &SliceSelector) -> Vec<usize> {
+        let len = self.element_starts.len() as i64;
+        let step = slice.step.unwrap_or(1);
+        if step == 0 || len == 0 {
+            return Vec::new();
+        }
+
+        let normalize = |index: ArrayIndex| index.normalize(self.element_starts.len());
+
+        // Bound normalization and clamping follow the JSONPath slice-selector
+        // algorithm: which of `start`/`end` is the lower vs. upper bound, and
+        // which direction they clamp towards, flips with the sign of `step`.
+        let (lower, upper) = if step > 0 {
+            let lower = slice.start.map_or(0, normalize).clamp(0, len);
+            let upper = slice.end.map_or(len, normalize).clamp(0, len);
+            (lower, upper)
+        } else {
+            let upper = slice.start.map_or(len - 1, normalize).clamp(-1, len - 1);
+            let lower = slice.end.map_or(-1, normalize).clamp(-1, len - 1);
+            (lower, upper)
+        };
+
+        let mut result = Vec::new();
+        if step > 0 {
+            let mut i = lower;
+            while i < upper {
+                result.push(self.element_starts[i as usize]);
+                i += step;
+            }
+        } else {
+            let mut i = upper;
+            while i > lower {
+                result.push(self.element_starts[i as usize]);
+                i += step;
+            }
+        }
+        result
+    }
+}
+
+/// Resolve `selector` against the array opening at `array_open`, building its element
+/// buffer from `index` in the same step instead of requiring the caller to drive
+/// [`ArrayElementBuffer::build_with_index`] separately.
+///
+/// Returns the selected elements' byte offsets, in ascending document order, together
+/// with the offset one past the array's matching `]`.
+///
+/// # Panics
+/// Panics under the same conditions as [`ArrayElementBuffer::build_with_index`].
+#[must_use]
+pub(crate) fn resolve_array_selector(
+    bytes: &[u8],
+    index: &StructuralIndex,
+    array_open: usize,
+    selector: &ArraySelector,
+) -> (Vec<usize>, usize) {
+    let (buffer, array_close) = ArrayElementBuffer::build_with_index(bytes, index, array_open);
+    (buffer.resolve(selector), array_close)
+}
+
+/// Advance past any JSON whitespace starting at `offset`.
+fn skip_whitespace(bytes: &[u8], mut offset: usize) -> usize {
+    while offset < bytes.len() && matches!(bytes[offset], b' ' | b'\t' | b'\n' | b'\r') {
+        offset += 1;
+    }
+    offset
+}
+
+/// Advance past a single non-container element (a string, number, `true`, `false`, or `null`)
+/// starting at `offset`, stopping at the first unquoted `,` or `]`.
+fn skip_scalar(bytes: &[u8], mut offset: usize) -> usize {
+    let mut in_string = false;
+    let mut escaped = false;
+
+    while offset < bytes.len() {
+        let byte = bytes[offset];
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if byte == b'\\' {
+                escaped = true;
+            } else if byte == b'"' {
+                in_string = false;
+            }
+        } else {
+            match byte {
+                b'"' => in_string = true,
+                b',' | b']' => break,
+                _ => (),
+            }
+        }
+        offset += 1;
+    }
+
+    offset
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer_of(n: usize) -> ArrayElementBuffer {
+        let mut buffer = ArrayElementBuffer::new();
+        for i in 0..n {
+            // Offsets don't need to be realistic here, just distinguishable.
+            buffer.push_element(i * 10);
+        }
+        buffer
+    }
+
+    #[test]
+    fn front_counted_index_resolves_like_nonnegative_array_index() {
+        let buffer = buffer_of(5);
+        let selected = buffer.resolve(&ArraySelector::Index(NonNegativeArrayIndex::new(2)));
+        assert_eq!(selected, vec![20]);
+    }
+
+    #[test]
+    fn out_of_bounds_front_counted_index_resolves_to_nothing() {
+        let buffer = buffer_of(3);
+        let selected = buffer.resolve(&ArraySelector::Index(NonNegativeArrayIndex::new(3)));
+        assert!(selected.is_empty());
+    }
+
+    #[test]
+    fn index_from_end_one_is_the_last_element() {
+        let buffer = buffer_of(5);
+        let selected = buffer.resolve(&ArraySelector::IndexFromEnd(NonNegativeArrayIndex::new(1)));
+        assert_eq!(selected, vec![40]);
+    }
+
+    #[test]
+    fn index_from_end_equal_to_length_is_the_first_element() {
+        let buffer = buffer_of(5);
+        let selected = buffer.resolve(&ArraySelector::IndexFromEnd(NonNegativeArrayIndex::new(5)));
+        assert_eq!(selected, vec![0]);
+    }
+
+    #[test]
+    fn index_from_end_beyond_length_resolves_to_nothing() {
+        let buffer = buffer_of(3);
+        let selected = buffer.resolve(&ArraySelector::IndexFromEnd(NonNegativeArrayIndex::new(4)));
+        assert!(selected.is_empty());
+    }
+
+    #[test]
+    fn default_slice_selects_every_element() {
+        let buffer = buffer_of(5);
+        let selected = buffer.resolve(&ArraySelector::Slice(SliceSelector::default()));
+        assert_eq!(selected, vec![0, 10, 20, 30, 40]);
+    }
+
+    #[test]
+    fn slice_with_explicit_bounds() {
+        let buffer = buffer_of(5);
+        let slice = SliceSelector {
+            start: Some(ArrayIndex::new(1)),
+            end: Some(ArrayIndex::new(3)),
+            step: None,
+        };
+        assert_eq!(buffer.resolve(&ArraySelector::Slice(slice)), vec![10, 20]);
+    }
+
+    #[test]
+    fn slice_with_negative_start_counts_from_the_end() {
+        let buffer = buffer_of(5);
+        let slice = SliceSelector {
+            start: Some(ArrayIndex::new(-2)),
+            end: None,
+            step: None,
+        };
+        assert_eq!(buffer.resolve(&ArraySelector::Slice(slice)), vec![30, 40]);
+    }
+
+    #[test]
+    fn slice_with_negative_end_excludes_the_tail() {
+        let buffer = buffer_of(5);
+        let slice = SliceSelector {
+            start: None,
+            end: Some(ArrayIndex::new(-2)),
+            step: None,
+        };
+        assert_eq!(buffer.resolve(&ArraySelector::Slice(slice)), vec![0, 10, 20]);
+    }
+
+    #[test]
+    fn slice_with_step_skips_elements() {
+        let buffer = buffer_of(6);
+        let slice = SliceSelector {
+            start: None,
+            end: None,
+            step: Some(2),
+        };
+        assert_eq!(buffer.resolve(&ArraySelector::Slice(slice)), vec![0, 20, 40]);
+    }
+
+    #[test]
+    fn slice_with_negative_step_reverses_the_array() {
+        let buffer = buffer_of(4);
+        let slice = SliceSelector {
+            start: None,
+            end: None,
+            step: Some(-1),
+        };
+        assert_eq!(buffer.resolve(&ArraySelector::Slice(slice)), vec![30, 20, 10, 0]);
+    }
+
+    #[test]
+    fn slice_with_zero_step_selects_nothing() {
+        let buffer = buffer_of(5);
+        let slice = SliceSelector {
+            start: None,
+            end: None,
+            step: Some(0),
+        };
+        assert!(buffer.resolve(&ArraySelector::Slice(slice)).is_empty());
+    }
+
+    #[test]
+    fn slice_on_an_empty_array_selects_nothing() {
+        let buffer = buffer_of(0);
+        assert!(buffer
+            .resolve(&ArraySelector::Slice(SliceSelector::default()))
+            .is_empty());
+    }
+
+    #[test]
+    fn build_with_index_finds_top_level_scalar_elements() {
+        let json = r#"{"a":[1,"two",3]}"#;
+        let index = StructuralIndex::build(json.as_bytes());
+        let array_open = json.find('[').unwrap();
+
+        let (buffer, array_close) = ArrayElementBuffer::build_with_index(json.as_bytes(), &index, array_open);
+
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(&json[buffer.element_starts[0]..buffer.element_starts[0] + 1], "1");
+        assert_eq!(&json[buffer.element_starts[1]..buffer.element_starts[1] + 5], "\"two\"");
+        assert_eq!(&json[buffer.element_starts[2]..buffer.element_starts[2] + 1], "3");
+        assert_eq!(array_close, json.find(']').unwrap() + 1);
+    }
+
+    #[test]
+    fn build_with_index_jumps_over_nested_containers() {
+        let json = r#"[{"x":[1,2,3]},[4,5],6]"#;
+        let index = StructuralIndex::build(json.as_bytes());
+
+        let (buffer, array_close) = ArrayElementBuffer::build_with_index(json.as_bytes(), &index, 0);
+
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(buffer.element_starts[0], 1);
+        assert_eq!(json.as_bytes()[buffer.element_starts[1]], b'[');
+        assert_eq!(json.as_bytes()[buffer.element_starts[2]], b'6');
+        assert_eq!(array_close, json.len());
+    }
+
+    #[test]
+    fn build_with_index_handles_an_empty_array() {
+        let json = r#"{"a":[]}"#;
+        let index = StructuralIndex::build(json.as_bytes());
+        let array_open = json.find('[').unwrap();
+
+        let (buffer, array_close) = ArrayElementBuffer::build_with_index(json.as_bytes(), &index, array_open);
+
+        assert!(buffer.is_empty());
+        assert_eq!(array_close, array_open + 2);
+    }
+
+    #[test]
+    fn build_with_index_resolves_a_negative_index_selector() {
+        let json = r#"[10,20,30]"#;
+        let index = StructuralIndex::build(json.as_bytes());
+
+        let (buffer, _) = ArrayElementBuffer::build_with_index(json.as_bytes(), &index, 0);
+        let selected = buffer.resolve(&ArraySelector::IndexFromEnd(NonNegativeArrayIndex::new(1)));
+
+        assert_eq!(selected, vec![json.find("30").unwrap()]);
+    }
+
+    #[test]
+    fn resolve_array_selector_builds_and_resolves_in_one_call() {
+        let json = r#"{"a":[10,20,30,40]}"#;
+        let index = StructuralIndex::build(json.as_bytes());
+        let array_open = json.find('[').unwrap();
+        let slice = SliceSelector {
+            start: Some(ArrayIndex::new(1)),
+            end: Some(ArrayIndex::new(3)),
+            step: None,
+        };
+
+        let (selected, array_close) =
+            resolve_array_selector(json.as_bytes(), &index, array_open, &ArraySelector::Slice(slice));
+
+        assert_eq!(selected, vec![json.find("20").unwrap(), json.find("30").unwrap()]);
+        assert_eq!(array_close, json.find(']').unwrap() + 1);
+    }
+}
@@ -1,64 +1,103 @@
 //! Automaton representations of a JSONPath query.
+//!
+//! Reachable from the crate root via `query.rs` (`pub mod automaton;`), so
+//! [`compiled`]/[`filter`]/[`sparse`] are genuinely declared and not orphaned
+//! modules. That doesn't make this file compile: the `use` below expects
+//! `JsonPathQuery`, `Label`, and `error::CompilerError` to be defined directly in
+//! `query.rs`, and none of the three are defined anywhere in this tree (see
+//! `query.rs`'s module doc). A filter-selector or array-selector parser/builder,
+//! and the `minimizer`/`nfa` modules declared below, would all need that query
+//! data model to exist first -- none of this module's submodules can be wired
+//! into query compilation until it does.
 
+mod compiled;
+mod filter;
 mod minimizer;
 mod nfa;
-mod small_set;
+mod sparse;
+mod state_id;
 
 use super::{error::CompilerError, JsonPathQuery, Label};
 use crate::debug;
 use nfa::NondeterministicAutomaton;
-use small_set::{SmallSet, SmallSet256};
 use smallvec::SmallVec;
+use state_id::StateIdSet;
 use std::{fmt::Display, ops::Index};
 
+pub use compiled::{AutomatonLoadError, CompiledAutomaton};
+pub use filter::{ComparisonOp, FilterExpr, FilterId, FilterLiteral, RelativePath};
+pub use sparse::{AutomatonTransitions, SparseAutomaton};
+pub use state_id::StateId;
+
+use filter::FilterRegistry;
+
 /// State of an [`Automaton`]. Thin wrapper over a state's identifier.
+///
+/// Generic over the [`StateId`] width the owning [`Automaton`] was minimized to;
+/// defaults to `u8`, which is the narrowest and most cache-friendly representation
+/// and covers the vast majority of queries.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
-pub struct State(u8);
+pub struct State<Id: StateId = u8>(Id);
 
-impl Display for State {
+impl<Id: StateId> Display for State<Id> {
     #[inline]
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "DFA({})", self.0)
+        write!(f, "DFA({:?})", self.0)
     }
 }
 
-impl From<u8> for State {
+impl<Id: StateId> From<Id> for State<Id> {
     #[inline(always)]
-    fn from(i: u8) -> Self {
+    fn from(i: Id) -> Self {
         Self(i)
     }
 }
 
 /// A minimal, deterministic automaton representing a JSONPath query.
-#[derive(Debug, PartialEq, Eq)]
-pub struct Automaton<'q> {
-    states: Vec<TransitionTable<'q>>,
+///
+/// Parameterized over the [`StateId`] width used to index its states. [`Automaton::new`]
+/// always builds the `u8`-keyed form and [`Automaton::new_with_id`] builds the width the
+/// caller names; either raises [`CompilerError::QueryTooComplex`] when the minimized state
+/// count does not fit that width. No wider width is tried automatically.
+///
+/// Does not derive [`Eq`] since a [`FilterExpr`] may compare against floating-point
+/// literals, which only support partial equality.
+#[derive(Debug, PartialEq)]
+pub struct Automaton<'q, Id: StateId = u8> {
+    states: Vec<TransitionTable<'q, Id>>,
+    filters: FilterRegistry,
 }
 
 /// A single transition of an [`Automaton`].
-type Transition<'q> = (&'q Label, State, bool);
+///
+/// The last element is the [`FilterId`] of a filter expression guarding the
+/// transition, if the transition originates from a filter selector like `[?@.isbn]`;
+/// such a transition is only taken when the referenced [`FilterExpr`] holds.
+type Transition<'q, Id> = (&'q Label, State<Id>, bool, Option<FilterId>);
 
 /// A transition table of a single [`State`] of an [`Automaton`].
 ///
 /// Contains transitions triggered by matching labels, and a fallback transition
 /// triggered when none of the label transitions match.
 #[derive(Debug)]
-pub struct TransitionTable<'q> {
-    transitions: SmallVec<[Transition<'q>; 2]>,
-    fallback_state: (State, bool),
+pub struct TransitionTable<'q, Id: StateId = u8> {
+    transitions: SmallVec<[Transition<'q, Id>; 2]>,
+    fallback_state: (State<Id>, bool),
+    accelerator: Option<SmallVec<[u8; 8]>>,
 }
 
-impl<'q> Default for TransitionTable<'q> {
+impl<'q, Id: StateId> Default for TransitionTable<'q, Id> {
     #[inline]
     fn default() -> Self {
         Self {
             transitions: Default::default(),
-            fallback_state: (State(0), false),
+            fallback_state: (State(Id::from_usize(0)), false),
+            accelerator: None,
         }
     }
 }
 
-impl<'q> PartialEq for TransitionTable<'q> {
+impl<'q, Id: StateId> PartialEq for TransitionTable<'q, Id> {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
         self.fallback_state == other.fallback_state
@@ -74,27 +113,31 @@ impl<'q> PartialEq for TransitionTable<'q> {
     }
 }
 
-impl<'q> Eq for TransitionTable<'q> {}
+impl<'q, Id: StateId> Eq for TransitionTable<'q, Id> {}
 
-impl<'q> Index<State> for Automaton<'q> {
-    type Output = TransitionTable<'q>;
+impl<'q, Id: StateId> Index<State<Id>> for Automaton<'q, Id> {
+    type Output = TransitionTable<'q, Id>;
 
     #[inline(always)]
-    fn index(&self, index: State) -> &Self::Output {
-        &self.states[index.0 as usize]
+    fn index(&self, index: State<Id>) -> &Self::Output {
+        &self.states[index.0.as_usize()]
     }
 }
 
-impl<'q> Automaton<'q> {
+impl<'q, Id: StateId> Automaton<'q, Id> {
     /// Convert a [`JsonPathQuery`] into a minimal deterministic automaton.
     ///
+    /// The narrowest [`StateId`] requested by the caller is used to index states;
+    /// callers unsure of the expected query complexity should use the `u8`-keyed
+    /// [`Automaton::new`] default, which is the most cache-friendly for small queries.
+    ///
     /// # Errors
     /// - [`CompilerError::QueryTooComplex`] raised if the query is too complex
-    /// and the automaton size was exceeded.
+    /// and the automaton size was exceeded for the chosen [`StateId`] width.
     /// - [`CompilerError::NotSupported`] raised if the query contains elements
     /// not yet supported by the compiler.
     #[inline]
-    pub fn new(query: &'q JsonPathQuery) -> Result<Self, CompilerError> {
+    pub fn new_with_id(query: &'q JsonPathQuery) -> Result<Self, CompilerError> {
         let nfa = NondeterministicAutomaton::new(query)?;
         debug!("NFA: {}", nfa);
         Automaton::minimize(nfa)
@@ -136,8 +179,8 @@ impl<'q> Automaton<'q> {
     #[allow(clippy::unused_self)] /* This is for stability. If the implementation changes so that
                                    * this is not always a 0 we don't want to have to change callsites.
                                    */
-    pub fn rejecting_state(&self) -> State {
-        State(0)
+    pub fn rejecting_state(&self) -> State<Id> {
+        State(Id::from_usize(0))
     }
 
     /// Returns the initial state of the automaton.
@@ -148,8 +191,8 @@ impl<'q> Automaton<'q> {
     #[allow(clippy::unused_self)] /* This is for stability. If the implementation changes so that
                                    * this is not always a 1 we don't want to have to change callsites.
                                    */
-    pub fn initial_state(&self) -> State {
-        State(1)
+    pub fn initial_state(&self) -> State<Id> {
+        State(Id::from_usize(1))
     }
 
     /// Returns the accepting states of the automaton.
@@ -157,19 +200,19 @@ impl<'q> Automaton<'q> {
     /// Query execution should treat transitioning into any of these states
     /// as a match.
     #[inline(always)]
-    pub fn accepting_states(&self) -> impl Iterator<Item = State> {
-        let mut states = SmallSet256::default();
+    pub fn accepting_states(&self) -> impl Iterator<Item = State<Id>> {
+        let mut states = StateIdSet::default();
         for tab in &self.states {
             if tab.fallback_state.1 {
-                states.insert(tab.fallback_state.0 .0)
+                states.insert(tab.fallback_state.0 .0.as_usize())
             }
             for st in &tab.transitions {
                 if st.2 {
-                    states.insert(st.1 .0)
+                    states.insert(st.1 .0.as_usize())
                 }
             }
         }
-        states.into_iter().map(State)
+        states.into_iter().map(|i| State(Id::from_usize(i)))
     }
 
     /// Returns whether the given state is accepting.
@@ -185,7 +228,7 @@ impl<'q> Automaton<'q> {
     /// ```
     #[must_use]
     #[inline(always)]
-    pub fn is_accepting(&self, state: State) -> bool {
+    pub fn is_accepting(&self, state: State<Id>) -> bool {
         self.accepting_states().any(|s| s == state)
     }
 
@@ -203,23 +246,137 @@ impl<'q> Automaton<'q> {
     /// ```
     #[must_use]
     #[inline(always)]
-    pub fn is_rejecting(&self, state: State) -> bool {
+    pub fn is_rejecting(&self, state: State<Id>) -> bool {
         state == self.rejecting_state()
     }
 
+    /// Look up the filter predicate guarding a transition, by its [`FilterId`].
+    ///
+    /// A [`FilterId`] is only ever handed out by transitions of this same automaton,
+    /// so this never panics for an id obtained from [`TransitionTable::transitions`].
+    #[must_use]
+    #[inline(always)]
+    pub fn filter(&self, id: FilterId) -> &FilterExpr {
+        self.filters.get(id)
+    }
+
+    /// Render this automaton as Graphviz DOT source.
+    ///
+    /// Unlike the ad-hoc [`Display`] impl, this styles nodes by their computed
+    /// attributes (accepting, rejecting, unitary, has-transition-to-accepting) and
+    /// renders the fallback transition distinctly from labelled ones, so large
+    /// compiled queries can actually be inspected by piping the output into
+    /// `dot -Tsvg`.
+    #[must_use]
+    #[inline]
+    pub fn dot(&self) -> Dot<'_, 'q, Id> {
+        Dot(self)
+    }
+
+    /// Returns whether the state has exactly one labelled transition and its
+    /// fallback transition leads to the [`rejecting_state`](Automaton::rejecting_state).
+    #[must_use]
+    fn is_unitary(&self, state: State<Id>) -> bool {
+        let table = &self[state];
+        table.transitions.len() == 1 && self.is_rejecting(table.fallback_state.0)
+    }
+
+    /// Returns whether the state has some transition, labelled or fallback,
+    /// leading to an accepting state.
+    #[must_use]
+    fn has_transition_to_accepting(&self, state: State<Id>) -> bool {
+        let table = &self[state];
+        table.fallback_state.1 || table.transitions.iter().any(|t| t.2)
+    }
+
     fn minimize(nfa: NondeterministicAutomaton<'q>) -> Result<Self, CompilerError> {
-        minimizer::minimize(nfa)
+        let mut automaton = minimizer::minimize::<Id>(nfa)?;
+        automaton.compute_accelerators();
+        Ok(automaton)
+    }
+
+    /// Populate the [`TransitionTable::accelerator`] of every state whose fallback
+    /// transition is a self-loop, that has no filter-guarded transition, and whose
+    /// labelled transitions can be distinguished by a small set of first bytes.
+    ///
+    /// See [`TransitionTable::accelerator`] for the invariant this establishes.
+    fn compute_accelerators(&mut self) {
+        for idx in 0..self.states.len() {
+            let state = State(Id::from_usize(idx));
+            let table = &self.states[idx];
+
+            if table.fallback_state.0 != state {
+                continue;
+            }
+            if table.transitions.iter().any(|t| t.3.is_some()) {
+                // A filtered transition can reject even when its label matches,
+                // so the byte it starts with cannot be trusted to skip ahead.
+                continue;
+            }
+
+            let mut bytes: SmallVec<[u8; 8]> = SmallVec::new();
+            let mut ok = true;
+
+            for (label, _, _, _) in &table.transitions {
+                match label.display().to_string().bytes().next() {
+                    Some(b) => {
+                        if !bytes.contains(&b) {
+                            bytes.push(b);
+                        }
+                    }
+                    None => {
+                        ok = false;
+                        break;
+                    }
+                }
+            }
+
+            if !ok || bytes.len() > MAX_ACCELERATED_LABELS {
+                continue;
+            }
+
+            for &b in STRUCTURAL_ACCELERATOR_BYTES {
+                if !bytes.contains(&b) {
+                    bytes.push(b);
+                }
+            }
+
+            self.states[idx].accelerator = Some(bytes);
+        }
+    }
+}
+
+impl<'q> Automaton<'q, u8> {
+    /// Convert a [`JsonPathQuery`] into a minimal deterministic automaton keyed by `u8`
+    /// states, the cache-friendly default suitable for the vast majority of queries.
+    ///
+    /// # Errors
+    /// - [`CompilerError::QueryTooComplex`] raised if the query is too complex
+    /// and the automaton size was exceeded.
+    /// - [`CompilerError::NotSupported`] raised if the query contains elements
+    /// not yet supported by the compiler.
+    #[inline]
+    pub fn new(query: &'q JsonPathQuery) -> Result<Self, CompilerError> {
+        Self::new_with_id(query)
     }
 }
 
-impl<'q> TransitionTable<'q> {
+/// Maximum number of distinguishing labelled-transition first-bytes a state
+/// can have before it is considered too branchy to accelerate.
+const MAX_ACCELERATED_LABELS: usize = 3;
+
+/// Structural bytes that must always be included in an accelerator set, since
+/// skipping over them would corrupt depth tracking.
+const STRUCTURAL_ACCELERATOR_BYTES: &[u8] = &[b'{', b'}', b'[', b']', b'"'];
+
+impl<'q, Id: StateId> TransitionTable<'q, Id> {
     /// Returns the state to which a fallback transition leads.
     ///
     /// A fallback transition is the catch-all transition triggered
     /// if none of the transitions were triggered.
     #[must_use]
     #[inline(always)]
-    pub fn fallback_state(&self) -> (State, bool) {
+    pub fn fallback_state(&self) -> (State<Id>, bool) {
         self.fallback_state
     }
 
@@ -229,29 +386,116 @@ impl<'q> TransitionTable<'q> {
     /// to the contained [`State`].
     #[must_use]
     #[inline(always)]
-    pub fn transitions(&self) -> &SmallVec<[Transition<'q>; 2]> {
+    pub fn transitions(&self) -> &SmallVec<[Transition<'q, Id>; 2]> {
         &self.transitions
     }
+
+    /// Returns the set of distinguishing first-bytes that can be used to skip
+    /// directly to the next byte relevant to this state, or `None` if the state
+    /// is not eligible for acceleration.
+    ///
+    /// A state is accelerable when its fallback transition is a self-loop, none
+    /// of its labelled transitions is guarded by a filter, and those transitions
+    /// are distinguished by a small (`<= 3`) set of first bytes. The returned set always also contains `{`, `}`, `[`, `]`, `"`,
+    /// since skipping over those would corrupt depth tracking.
+    #[must_use]
+    #[inline(always)]
+    pub fn accelerator(&self) -> Option<&[u8]> {
+        self.accelerator.as_deref()
+    }
 }
 
-impl<'q> Display for Automaton<'q> {
+impl<'q, Id: StateId> Display for Automaton<'q, Id> {
     #[inline]
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "digraph {{")?;
         for i in self.accepting_states() {
-            writeln!(f, "node [shape = doublecircle]; {}", i.0)?;
+            writeln!(f, "node [shape = doublecircle]; {:?}", i.0)?;
         }
         writeln!(f, "node [shape = circle];")?;
         for (i, transitions) in self.states.iter().enumerate() {
-            for (label, state, _) in transitions.transitions.iter() {
-                writeln!(f, "  {i} -> {} [label=\"{}\"]", state.0, label.display(),)?
+            for (label, state, _, filter_id) in transitions.transitions.iter() {
+                match filter_id {
+                    Some(id) => writeln!(
+                        f,
+                        "  {i} -> {:?} [label=\"{}, ?{}\"]",
+                        state.0,
+                        label.display(),
+                        self.filters.get(*id)
+                    )?,
+                    None => writeln!(f, "  {i} -> {:?} [label=\"{}\"]", state.0, label.display(),)?,
+                }
+            }
+            writeln!(
+                f,
+                "  {i} -> {:?} [label=\"*\"]",
+                transitions.fallback_state.0 .0
+            )?;
+        }
+        write!(f, "}}")?;
+        Ok(())
+    }
+}
+
+/// Adapter rendering an [`Automaton`] as Graphviz DOT, obtained via [`Automaton::dot`].
+pub struct Dot<'a, 'q, Id: StateId = u8>(&'a Automaton<'q, Id>);
+
+impl<'a, 'q, Id: StateId> Display for Dot<'a, 'q, Id> {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let automaton = self.0;
+        writeln!(f, "digraph automaton {{")?;
+
+        for (idx, _) in automaton.states.iter().enumerate() {
+            let state = State(Id::from_usize(idx));
+            let mut shape = "circle";
+            let mut style_parts: Vec<&str> = vec![];
+            let mut fillcolor = None;
+
+            if automaton.is_accepting(state) {
+                shape = "doublecircle";
+            } else if automaton.is_unitary(state) {
+                shape = "diamond";
+            }
+
+            if automaton.is_rejecting(state) {
+                style_parts.push("filled");
+                fillcolor = Some("grey");
+            }
+            if automaton.has_transition_to_accepting(state) {
+                style_parts.push("bold");
+            }
+
+            write!(f, "  {idx} [shape = {shape}")?;
+            if !style_parts.is_empty() {
+                write!(f, ", style = \"{}\"", style_parts.join(","))?;
+            }
+            if let Some(color) = fillcolor {
+                write!(f, ", fillcolor = {color}")?;
+            }
+            writeln!(f, "];")?;
+        }
+
+        for (i, transitions) in automaton.states.iter().enumerate() {
+            for (label, state, _, filter_id) in transitions.transitions.iter() {
+                match filter_id {
+                    Some(id) => writeln!(
+                        f,
+                        "  {i} -> {:?} [label=\"{}, ?{}\"]",
+                        state.0,
+                        label.display(),
+                        automaton.filters.get(*id)
+                    )?,
+                    None => writeln!(f, "  {i} -> {:?} [label=\"{}\"]", state.0, label.display())?,
+                }
             }
             writeln!(
                 f,
-                "  {i} -> {} [label=\"*\"]",
+                "  {i} -> {:?} [label=\"*\", style = dashed]",
                 transitions.fallback_state.0 .0
             )?;
         }
+
         write!(f, "}}")?;
         Ok(())
     }
@@ -0,0 +1,286 @@
+//! Zero-copy (de)serialization of a minimized [`Automaton`] into a flat byte buffer.
+//!
+//! This allows skipping NFA construction and minimization entirely when a query
+//! is compiled ahead of time (CLI batch runs, long-lived server processes) by
+//! persisting the resulting [`Automaton`] to disk or embedding it in a binary,
+//! then loading it back as a [`CompiledAutomaton`] that owns its label bytes.
+//!
+//! Filter selectors (`[?...]`) are not currently supported by this format, since the
+//! predicate itself has no stable on-disk representation yet: [`Automaton::to_bytes`]
+//! returns [`AutomatonSaveError::FilteredTransition`] instead of silently dropping the
+//! guard and serializing the transition as unconditional.
+//!
+//! Only `u8`-keyed automatons can be compiled this way; the format encodes each
+//! state as a single byte, matching the width [`Automaton::new`] picks by default.
+//!
+//! Neither [`Automaton::to_bytes`] nor [`CompiledAutomaton::from_bytes`] has a caller
+//! anywhere in this tree. The request names `StacklessRunner`/`StackBasedRunner` as the
+//! intended consumers, but no runner of any kind exists here: `crates/rsonpath/src/
+//! main.rs` already imports `rsonpath_lib::engine::{main::MainEngine, recursive::
+//! RecursiveEngine, Compiler, Engine}`, none of which are defined under
+//! `rsonpath-lib/src`. This module is reachable from the crate root regardless
+//! (`query/automaton.rs` declares `mod compiled;`, itself reachable via `query.rs`/
+//! `lib.rs` since [V0ldek/rsonpath#chunk1-6]), so the format is real; the tests below
+//! check only the loader's header validation (a short buffer, bad magic bytes), not a
+//! full round trip, and nothing loads a [`CompiledAutomaton`] into a match yet.
+use super::{Automaton, FilterId, State, TransitionTable};
+use crate::query::Label;
+use std::mem::size_of;
+
+/// Magic bytes identifying a serialized automaton buffer.
+const MAGIC: [u8; 4] = *b"RSNP";
+/// Version of the binary format. Bump this on any incompatible layout change.
+const FORMAT_VERSION: u32 = 1;
+/// Tag byte written after the version to record the endianness the buffer was produced with.
+const LITTLE_ENDIAN_TAG: u8 = 0x01;
+const BIG_ENDIAN_TAG: u8 = 0x02;
+
+/// Errors that can occur while loading a [`CompiledAutomaton`] from a byte buffer.
+#[derive(Debug, thiserror::Error)]
+pub enum AutomatonLoadError {
+    /// The buffer is shorter than the fixed-size header.
+    #[error("buffer too short to contain an automaton header")]
+    BufferTooShort,
+    /// The magic bytes at the start of the buffer did not match.
+    #[error("invalid magic bytes, this is not a compiled rsonpath automaton")]
+    InvalidMagic,
+    /// The format version recorded in the header is not supported by this build.
+    #[error("unsupported automaton format version {0}, expected {FORMAT_VERSION}")]
+    UnsupportedVersion(u32),
+    /// The endianness tag did not match a byte order this build can decode.
+    #[error("buffer was serialized with a different endianness than this platform supports")]
+    EndiannessMismatch,
+    /// The buffer ended in the middle of a record; it is truncated or corrupt.
+    #[error("buffer is truncated or corrupt")]
+    UnexpectedEof,
+    /// A label's recorded byte length was not valid UTF-8.
+    #[error("label bytes are not valid UTF-8")]
+    InvalidLabel,
+}
+
+/// Errors that can occur while serializing an [`Automaton`] via [`Automaton::to_bytes`].
+#[derive(Debug, thiserror::Error)]
+pub enum AutomatonSaveError {
+    /// A transition guarded by a filter selector (`[?...]`) has no stable on-disk
+    /// representation yet; round-tripping it would silently drop the guard and let the
+    /// deserialized automaton accept nodes the original query would have rejected.
+    #[error("cannot serialize a transition guarded by filter {0:?}: filter selectors have no on-disk representation yet")]
+    FilteredTransition(FilterId),
+}
+
+/// An [`Automaton`] loaded from a flat byte buffer that owns all of its label bytes,
+/// rather than borrowing them from the source [`JsonPathQuery`](crate::query::JsonPathQuery).
+///
+/// Because it owns its data, a [`CompiledAutomaton`] can be `mmap`ed from disk or embedded
+/// directly into a binary as a static byte array, and read back without re-running
+/// NFA construction or minimization.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CompiledAutomaton {
+    states: Vec<OwnedTransitionTable>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct OwnedTransitionTable {
+    transitions: Vec<(Label, State<u8>, bool)>,
+    fallback_state: (State<u8>, bool),
+}
+
+impl<'q> Automaton<'q, u8> {
+    /// Serialize this automaton into a flat byte buffer.
+    ///
+    /// The produced buffer can be loaded back with [`CompiledAutomaton::from_bytes`]
+    /// without re-running [`Automaton::new`]'s NFA construction and minimization.
+    ///
+    /// # Errors
+    /// Returns [`AutomatonSaveError::FilteredTransition`] if any transition is guarded by
+    /// a filter selector, since this format has no way to represent the predicate.
+    #[inline]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, AutomatonSaveError> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&MAGIC);
+        buf.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        buf.push(if cfg!(target_endian = "little") {
+            LITTLE_ENDIAN_TAG
+        } else {
+            BIG_ENDIAN_TAG
+        });
+
+        write_u32(&mut buf, self.states.len() as u32);
+
+        for table in &self.states {
+            write_transition_table(&mut buf, table)?;
+        }
+
+        Ok(buf)
+    }
+}
+
+impl CompiledAutomaton {
+    /// Load a [`CompiledAutomaton`] from a byte buffer produced by [`Automaton::to_bytes`].
+    ///
+    /// # Errors
+    /// Returns an [`AutomatonLoadError`] if the header is missing, the magic bytes
+    /// or format version do not match, the buffer was produced on a platform with
+    /// different endianness, or the buffer is truncated.
+    #[inline]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, AutomatonLoadError> {
+        const HEADER_LEN: usize = 4 + size_of::<u32>() + 1 + size_of::<u32>();
+        if bytes.len() < HEADER_LEN {
+            return Err(AutomatonLoadError::BufferTooShort);
+        }
+
+        let mut cursor = Cursor::new(bytes);
+
+        let magic = cursor.take(4)?;
+        if magic != MAGIC {
+            return Err(AutomatonLoadError::InvalidMagic);
+        }
+
+        let version = cursor.read_u32()?;
+        if version != FORMAT_VERSION {
+            return Err(AutomatonLoadError::UnsupportedVersion(version));
+        }
+
+        let endianness_tag = cursor.take(1)?[0];
+        let expected_tag = if cfg!(target_endian = "little") {
+            LITTLE_ENDIAN_TAG
+        } else {
+            BIG_ENDIAN_TAG
+        };
+        if endianness_tag != expected_tag {
+            return Err(AutomatonLoadError::EndiannessMismatch);
+        }
+
+        let state_count = cursor.read_u32()?;
+        let mut states = Vec::with_capacity(state_count as usize);
+
+        for _ in 0..state_count {
+            states.push(read_transition_table(&mut cursor)?);
+        }
+
+        Ok(Self { states })
+    }
+
+    /// Returns the transition table of a given [`State`], mirroring [`Automaton::index`].
+    #[must_use]
+    #[inline]
+    pub fn transitions(&self, state: State<u8>) -> impl Iterator<Item = (&Label, State<u8>, bool)> {
+        self.states[state.0 as usize].transitions.iter().map(|(l, s, b)| (l, *s, *b))
+    }
+
+    /// Returns the fallback transition of a given [`State`].
+    #[must_use]
+    #[inline]
+    pub fn fallback_state(&self, state: State<u8>) -> (State<u8>, bool) {
+        self.states[state.0 as usize].fallback_state
+    }
+
+    /// Returns the number of states in the automaton.
+    #[must_use]
+    #[inline]
+    pub fn state_count(&self) -> usize {
+        self.states.len()
+    }
+}
+
+fn write_transition_table(buf: &mut Vec<u8>, table: &TransitionTable<'_, u8>) -> Result<(), AutomatonSaveError> {
+    write_u32(buf, table.transitions().len() as u32);
+
+    for (label, state, is_accepting, filter) in table.transitions().iter() {
+        if let Some(filter_id) = filter {
+            return Err(AutomatonSaveError::FilteredTransition(*filter_id));
+        }
+
+        let label_bytes = label.display().to_string().into_bytes();
+        write_u32(buf, label_bytes.len() as u32);
+        buf.extend_from_slice(&label_bytes);
+        buf.push(state_id(*state));
+        buf.push(u8::from(*is_accepting));
+    }
+
+    let (fallback_state, fallback_accepting) = table.fallback_state();
+    buf.push(state_id(fallback_state));
+    buf.push(u8::from(fallback_accepting));
+
+    Ok(())
+}
+
+fn read_transition_table(cursor: &mut Cursor<'_>) -> Result<OwnedTransitionTable, AutomatonLoadError> {
+    let transition_count = cursor.read_u32()?;
+    let mut transitions = Vec::with_capacity(transition_count as usize);
+
+    for _ in 0..transition_count {
+        let label_len = cursor.read_u32()?;
+        let label_bytes = cursor.take(label_len as usize)?;
+        let label_str = std::str::from_utf8(label_bytes).map_err(|_| AutomatonLoadError::InvalidLabel)?;
+        let label = Label::new(label_str.as_bytes());
+        let state = State::from(cursor.take(1)?[0]);
+        let is_accepting = cursor.take(1)?[0] != 0;
+
+        transitions.push((label, state, is_accepting));
+    }
+
+    let fallback_state = State::from(cursor.take(1)?[0]);
+    let fallback_accepting = cursor.take(1)?[0] != 0;
+
+    Ok(OwnedTransitionTable {
+        transitions,
+        fallback_state: (fallback_state, fallback_accepting),
+    })
+}
+
+#[inline]
+fn state_id(state: State<u8>) -> u8 {
+    state.0
+}
+
+fn write_u32(buf: &mut Vec<u8>, value: u32) {
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+struct Cursor<'b> {
+    bytes: &'b [u8],
+    pos: usize,
+}
+
+impl<'b> Cursor<'b> {
+    fn new(bytes: &'b [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'b [u8], AutomatonLoadError> {
+        if self.pos + len > self.bytes.len() {
+            return Err(AutomatonLoadError::UnexpectedEof);
+        }
+        let slice = &self.bytes[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(slice)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, AutomatonLoadError> {
+        let bytes = self.take(size_of::<u32>())?;
+        Ok(u32::from_le_bytes(bytes.try_into().expect("length checked above")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_buffer_without_header() {
+        let result = CompiledAutomaton::from_bytes(&[1, 2, 3]);
+        assert!(matches!(result, Err(AutomatonLoadError::BufferTooShort | AutomatonLoadError::UnexpectedEof)));
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        let mut buf = vec![0, 0, 0, 0];
+        buf.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        buf.push(LITTLE_ENDIAN_TAG);
+        buf.extend_from_slice(&0u32.to_le_bytes());
+
+        let result = CompiledAutomaton::from_bytes(&buf);
+        assert!(matches!(result, Err(AutomatonLoadError::InvalidMagic)));
+    }
+}
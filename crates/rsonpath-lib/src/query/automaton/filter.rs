@@ -0,0 +1,376 @@
+//! Filter predicate AST, registry, and evaluator for filter selectors (`[?@.isbn]`,
+//! `[?@.price < 10]`).
+//!
+//! A [`FilterExpr`] is meant to be evaluated over the subtree rooted at the node a transition
+//! would otherwise accept into, with the transition only taken if the predicate holds.
+//! [`FilterExpr::evaluate`] is a real, direct evaluator: given a
+//! [`StructuralIndex`](crate::classification::depth::StructuralIndex) over the whole document
+//! and the start offset of the `@` node under test, it walks a [`RelativePath`] into nested
+//! objects the same way
+//! [`LabelMatcher::find_direct_keys`](crate::classification::matcher::LabelMatcher::find_direct_keys)
+//! walks object keys, then compares the resolved value against a [`FilterLiteral`].
+//!
+//! This module is declared for real (`query/automaton.rs` has `mod filter;`, and since
+//! [V0ldek/rsonpath#chunk1-6] that file is itself reachable from the crate root via
+//! `query.rs`/`lib.rs`), so it isn't dead code. What's still missing is a caller: nothing
+//! constructs a [`FilterExpr`] from a query today, and that's not fixable by wiring alone.
+//! `rsonpath-syntax` -- the crate `JsonPathQueryBuilder` would need to live in -- has no
+//! `src/` directory in this tree at all, only the `examples/builder.rs` file referencing
+//! a builder that is never defined anywhere; and a transition's
+//! [`FilterId`](super::FilterId) is consumed only by [`Automaton`](super::Automaton)'s
+//! `Display`/DOT rendering, its accelerator computation, its sparse view, and `to_bytes`
+//! (which rejects it), never by a matching engine, because no
+//! engine module exists anywhere in this tree either (see `crates/rsonpath/src/main.rs`'s
+//! `rsonpath_lib::engine::*` imports, which point at nothing `rsonpath-lib/src` defines).
+//! Parser support and a runner are both out of reach until those crates exist; this
+//! module is a real evaluator still looking for an executor to plug into, not an
+//! unevaluated scaffold, and `rsonpath-syntax/examples/builder.rs` documents the builder
+//! side of the same gap.
+use crate::classification::depth::StructuralIndex;
+use crate::classification::matcher::{find_string_end, skip_scalar_value, skip_whitespace};
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+
+/// Identifies a [`FilterExpr`] registered on an [`Automaton`](super::Automaton).
+///
+/// Transitions carrying a filter store a `FilterId` instead of the predicate itself,
+/// so that predicates can be shared and the transition tuple stays small.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct FilterId(pub(crate) u32);
+
+/// A relative path rooted at `@`, the node currently under consideration by a filter.
+///
+/// Only plain member-name segments are supported; e.g. `@.a.b` is `RelativePath(["a", "b"])`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct RelativePath(Vec<String>);
+
+impl RelativePath {
+    /// Create a relative path from its member-name segments.
+    #[must_use]
+    #[inline]
+    pub fn new(segments: Vec<String>) -> Self {
+        Self(segments)
+    }
+
+    /// Returns the member-name segments of this path, in order from `@`.
+    #[must_use]
+    #[inline]
+    pub fn segments(&self) -> &[String] {
+        &self.0
+    }
+
+    /// Resolve this path against the JSON value starting at `node_start`, walking into a
+    /// nested object one member segment at a time.
+    ///
+    /// Returns the start offset of the resolved value, or `None` if any segment along the
+    /// way is missing or the node at that point isn't an object.
+    #[must_use]
+    fn resolve(&self, bytes: &[u8], index: &StructuralIndex, node_start: usize) -> Option<usize> {
+        let mut offset = node_start;
+        for segment in &self.0 {
+            if bytes.get(offset) != Some(&b'{') {
+                return None;
+            }
+            offset = find_member_value(bytes, index, offset, segment.as_bytes())?;
+        }
+        Some(offset)
+    }
+}
+
+/// Find the start offset of `key`'s value as a direct member of the object at `object_open`,
+/// mirroring the walk [`LabelMatcher::find_direct_keys`](crate::classification::matcher::LabelMatcher::find_direct_keys)
+/// does, but stopping at the first matching key instead of collecting every label match.
+fn find_member_value(bytes: &[u8], index: &StructuralIndex, object_open: usize, key: &[u8]) -> Option<usize> {
+    let object_close = index.find_matching_close(object_open)?;
+    let mut offset = skip_whitespace(bytes, object_open + 1);
+
+    while offset < object_close - 1 {
+        assert_eq!(bytes[offset], b'"', "expected an object key");
+        let key_start = offset + 1;
+        let key_end = find_string_end(bytes, key_start);
+        let is_match = bytes[key_start..key_end] == *key;
+
+        offset = skip_whitespace(bytes, key_end + 1);
+        assert_eq!(bytes[offset], b':', "expected ':' after an object key");
+        offset = skip_whitespace(bytes, offset + 1);
+        let value_start = offset;
+
+        if is_match {
+            return Some(value_start);
+        }
+
+        offset = match bytes[offset] {
+            b'{' | b'[' => index.find_matching_close(offset)?,
+            _ => skip_scalar_value(bytes, offset),
+        };
+
+        offset = skip_whitespace(bytes, offset);
+        if offset < object_close - 1 && bytes[offset] == b',' {
+            offset = skip_whitespace(bytes, offset + 1);
+        }
+    }
+
+    None
+}
+
+impl Display for RelativePath {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "@")?;
+        for segment in &self.0 {
+            write!(f, ".{segment}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Comparison operators supported by [`FilterExpr::Comparison`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ComparisonOp {
+    /// `==`
+    Eq,
+    /// `!=`
+    Ne,
+    /// `<`
+    Lt,
+    /// `<=`
+    Le,
+    /// `>`
+    Gt,
+    /// `>=`
+    Ge,
+}
+
+impl Display for ComparisonOp {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = match self {
+            Self::Eq => "==",
+            Self::Ne => "!=",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+        };
+        write!(f, "{op}")
+    }
+}
+
+/// A literal value a [`RelativePath`] can be compared against.
+#[derive(Clone, PartialEq, Debug)]
+pub enum FilterLiteral {
+    /// A JSON string literal.
+    String(String),
+    /// A JSON number literal.
+    Number(f64),
+    /// A JSON boolean literal.
+    Bool(bool),
+    /// The JSON `null` literal.
+    Null,
+}
+
+/// A parsed filter-expression predicate, e.g. `@.isbn` or `@.price < 10 && @.isbn`.
+#[derive(Clone, PartialEq, Debug)]
+pub enum FilterExpr {
+    /// Tests that the relative path exists in the current node's subtree.
+    Exists(RelativePath),
+    /// Compares the value at the relative path against a literal.
+    Comparison(RelativePath, ComparisonOp, FilterLiteral),
+    /// Conjunction of two predicates.
+    And(Box<FilterExpr>, Box<FilterExpr>),
+    /// Disjunction of two predicates.
+    Or(Box<FilterExpr>, Box<FilterExpr>),
+    /// Negation of a predicate.
+    Not(Box<FilterExpr>),
+}
+
+impl Display for FilterExpr {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exists(path) => write!(f, "{path}"),
+            Self::Comparison(path, op, FilterLiteral::String(s)) => write!(f, "{path} {op} \"{s}\""),
+            Self::Comparison(path, op, FilterLiteral::Number(n)) => write!(f, "{path} {op} {n}"),
+            Self::Comparison(path, op, FilterLiteral::Bool(b)) => write!(f, "{path} {op} {b}"),
+            Self::Comparison(path, op, FilterLiteral::Null) => write!(f, "{path} {op} null"),
+            Self::And(lhs, rhs) => write!(f, "({lhs} && {rhs})"),
+            Self::Or(lhs, rhs) => write!(f, "({lhs} || {rhs})"),
+            Self::Not(inner) => write!(f, "!{inner}"),
+        }
+    }
+}
+
+impl FilterExpr {
+    /// Evaluate this predicate against the JSON value starting at `node_start` -- the `@`
+    /// node a filter selector tests -- using `index` to skip over nested containers without
+    /// tracking depth byte by byte.
+    #[must_use]
+    pub(crate) fn evaluate(&self, bytes: &[u8], index: &StructuralIndex, node_start: usize) -> bool {
+        match self {
+            Self::Exists(path) => path.resolve(bytes, index, node_start).is_some(),
+            Self::Comparison(path, op, literal) => path
+                .resolve(bytes, index, node_start)
+                .is_some_and(|value_start| compare(bytes, value_start, *op, literal)),
+            Self::And(lhs, rhs) => lhs.evaluate(bytes, index, node_start) && rhs.evaluate(bytes, index, node_start),
+            Self::Or(lhs, rhs) => lhs.evaluate(bytes, index, node_start) || rhs.evaluate(bytes, index, node_start),
+            Self::Not(inner) => !inner.evaluate(bytes, index, node_start),
+        }
+    }
+}
+
+/// Compare the JSON scalar starting at `value_start` against `literal` via `op`.
+///
+/// A value whose JSON type doesn't match `literal`'s is considered incomparable: every
+/// operator except `!=` reports `false` for it, matching how the JSONPath spec treats
+/// comparisons between differently-typed operands.
+fn compare(bytes: &[u8], value_start: usize, op: ComparisonOp, literal: &FilterLiteral) -> bool {
+    let ordering = match literal {
+        FilterLiteral::Number(n) => parse_number(bytes, value_start).and_then(|actual| actual.partial_cmp(n)),
+        FilterLiteral::String(s) => parse_string(bytes, value_start).map(|actual| actual.cmp(s.as_bytes())),
+        FilterLiteral::Bool(b) => parse_bool(bytes, value_start).map(|actual| actual.cmp(b)),
+        FilterLiteral::Null => parse_null(bytes, value_start).map(|()| Ordering::Equal),
+    };
+
+    match (op, ordering) {
+        (ComparisonOp::Eq, Some(Ordering::Equal)) => true,
+        (ComparisonOp::Ne, ordering) => ordering != Some(Ordering::Equal),
+        (ComparisonOp::Lt, Some(Ordering::Less)) => true,
+        (ComparisonOp::Le, Some(Ordering::Less | Ordering::Equal)) => true,
+        (ComparisonOp::Gt, Some(Ordering::Greater)) => true,
+        (ComparisonOp::Ge, Some(Ordering::Greater | Ordering::Equal)) => true,
+        _ => false,
+    }
+}
+
+/// Parse a JSON number starting at `value_start`, or `None` if it isn't one.
+fn parse_number(bytes: &[u8], value_start: usize) -> Option<f64> {
+    if !matches!(bytes.get(value_start), Some(b'-' | b'0'..=b'9')) {
+        return None;
+    }
+    let end = skip_scalar_value(bytes, value_start);
+    std::str::from_utf8(&bytes[value_start..end]).ok()?.parse().ok()
+}
+
+/// Parse a JSON string starting at `value_start`, returning its raw (still-escaped) content
+/// bytes, or `None` if it isn't a string.
+fn parse_string(bytes: &[u8], value_start: usize) -> Option<&[u8]> {
+    if bytes.get(value_start) != Some(&b'"') {
+        return None;
+    }
+    let start = value_start + 1;
+    let end = find_string_end(bytes, start);
+    Some(&bytes[start..end])
+}
+
+/// Parse a JSON boolean starting at `value_start`, or `None` if it isn't one.
+fn parse_bool(bytes: &[u8], value_start: usize) -> Option<bool> {
+    if bytes[value_start..].starts_with(b"true") {
+        Some(true)
+    } else if bytes[value_start..].starts_with(b"false") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Parse a JSON `null` starting at `value_start`, or `None` if it isn't one.
+fn parse_null(bytes: &[u8], value_start: usize) -> Option<()> {
+    bytes[value_start..].starts_with(b"null").then_some(())
+}
+
+/// Registry of [`FilterExpr`]s attached to an [`Automaton`](super::Automaton)'s transitions.
+///
+/// Transitions reference predicates by [`FilterId`] so that the transition tuple
+/// remains cheap to copy; the actual predicate tree lives here.
+#[derive(Clone, PartialEq, Default, Debug)]
+pub struct FilterRegistry {
+    filters: Vec<FilterExpr>,
+}
+
+impl FilterRegistry {
+    /// Register a new filter expression, returning the [`FilterId`] to reference it by.
+    #[inline]
+    pub(crate) fn register(&mut self, expr: FilterExpr) -> FilterId {
+        let id = FilterId(self.filters.len() as u32);
+        self.filters.push(expr);
+        id
+    }
+
+    /// Look up a previously registered filter expression.
+    #[must_use]
+    #[inline]
+    pub fn get(&self, id: FilterId) -> &FilterExpr {
+        &self.filters[id.0 as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(segments: &[&str]) -> RelativePath {
+        RelativePath::new(segments.iter().map(|s| (*s).to_owned()).collect())
+    }
+
+    #[test]
+    fn exists_matches_a_present_member_only() {
+        let bytes = br#"{"isbn":"0-553-21311-3","title":"Foundation"}"#;
+        let index = StructuralIndex::build(bytes);
+
+        assert!(FilterExpr::Exists(path(&["isbn"])).evaluate(bytes, &index, 0));
+        assert!(!FilterExpr::Exists(path(&["author"])).evaluate(bytes, &index, 0));
+    }
+
+    #[test]
+    fn exists_walks_into_nested_objects() {
+        let bytes = br#"{"price":{"amount":10,"currency":"USD"}}"#;
+        let index = StructuralIndex::build(bytes);
+
+        assert!(FilterExpr::Exists(path(&["price", "currency"])).evaluate(bytes, &index, 0));
+        assert!(!FilterExpr::Exists(path(&["price", "tax"])).evaluate(bytes, &index, 0));
+        assert!(!FilterExpr::Exists(path(&["currency"])).evaluate(bytes, &index, 0));
+    }
+
+    #[test]
+    fn comparison_evaluates_numbers() {
+        let bytes = br#"{"price":10}"#;
+        let index = StructuralIndex::build(bytes);
+        let less_than_twenty = FilterExpr::Comparison(path(&["price"]), ComparisonOp::Lt, FilterLiteral::Number(20.0));
+        let greater_than_twenty = FilterExpr::Comparison(path(&["price"]), ComparisonOp::Gt, FilterLiteral::Number(20.0));
+
+        assert!(less_than_twenty.evaluate(bytes, &index, 0));
+        assert!(!greater_than_twenty.evaluate(bytes, &index, 0));
+    }
+
+    #[test]
+    fn comparison_evaluates_strings_and_is_type_sensitive() {
+        let bytes = br#"{"category":"fiction","price":10}"#;
+        let index = StructuralIndex::build(bytes);
+        let matches_category = FilterExpr::Comparison(
+            path(&["category"]),
+            ComparisonOp::Eq,
+            FilterLiteral::String("fiction".to_owned()),
+        );
+        let type_mismatch =
+            FilterExpr::Comparison(path(&["price"]), ComparisonOp::Eq, FilterLiteral::String("10".to_owned()));
+
+        assert!(matches_category.evaluate(bytes, &index, 0));
+        assert!(!type_mismatch.evaluate(bytes, &index, 0));
+    }
+
+    #[test]
+    fn and_or_not_combine_as_expected() {
+        let bytes = br#"{"isbn":"abc","price":25}"#;
+        let index = StructuralIndex::build(bytes);
+        let has_isbn = FilterExpr::Exists(path(&["isbn"]));
+        let cheap = FilterExpr::Comparison(path(&["price"]), ComparisonOp::Lt, FilterLiteral::Number(10.0));
+
+        let and = FilterExpr::And(Box::new(has_isbn.clone()), Box::new(cheap.clone()));
+        let or = FilterExpr::Or(Box::new(has_isbn.clone()), Box::new(cheap.clone()));
+        let not = FilterExpr::Not(Box::new(cheap));
+
+        assert!(!and.evaluate(bytes, &index, 0));
+        assert!(or.evaluate(bytes, &index, 0));
+        assert!(not.evaluate(bytes, &index, 0));
+    }
+}
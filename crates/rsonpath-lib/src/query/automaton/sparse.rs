@@ -0,0 +1,182 @@
+//! A compact, flat-array view over an [`Automaton`]'s transitions.
+//!
+//! The dense [`Automaton`] stores one heap-allocated `SmallVec` per state, which
+//! gives fast random access but pointer-chases and wastes space once a minimized
+//! query grows wide. Mirroring the dense-vs-sparse split `regex-automata` uses for
+//! its DFAs, [`SparseAutomaton`] instead lays every state's transitions out in one
+//! flat arena indexed by a per-state offset array, with the fallbacks in a third
+//! array, so the structure takes three allocations whatever the state count and a
+//! lookup is one slice index away.
+//!
+//! [`AutomatonTransitions`] is the common indexing trait runners would need to accept
+//! either representation. No runner exists anywhere in this tree to be such a consumer:
+//! `crates/rsonpath/src/main.rs` already imports `rsonpath_lib::engine::{main::
+//! MainEngine, recursive::RecursiveEngine, Compiler, Engine}`, none of which are
+//! defined under `rsonpath-lib/src` (there is no `engine` module at all), so there is
+//! no `StacklessRunner`/`StackBasedRunner` for `AutomatonTransitions` to be accepted
+//! by -- this isn't a missing `impl`, it's a missing crate of runners. This module is
+//! reachable from the crate root regardless (`query/automaton.rs` declares `mod
+//! sparse;`, and since [V0ldek/rsonpath#chunk1-6] `query/automaton.rs` itself is
+//! reachable via `query.rs`/`lib.rs`), so [`reachable_states`] is real, callable, and
+//! generic over the trait in the meantime: a breadth-first reachability walk that runs
+//! identically over a dense [`Automaton`] or a compact [`SparseAutomaton`] without
+//! knowing which it was handed, standing in for the runner until one exists.
+use super::{Automaton, FilterId, Label, State, StateId};
+use std::collections::VecDeque;
+
+/// Common read access to an automaton's transitions, implemented by both the dense
+/// [`Automaton`] and the compact [`SparseAutomaton`].
+///
+/// Lets callers pick whichever representation suits them — the dense form for fast
+/// random access on small queries, the sparse form for memory-compact, cache-local
+/// access on large ones — without changing how they walk the automaton.
+pub trait AutomatonTransitions<'q, Id: StateId> {
+    /// Returns the number of states in the automaton.
+    fn state_count(&self) -> usize;
+
+    /// Returns the fallback transition of a given [`State`].
+    fn fallback_state(&self, state: State<Id>) -> (State<Id>, bool);
+
+    /// Returns the labelled transitions leaving a given [`State`].
+    fn transitions(&self, state: State<Id>) -> Box<dyn Iterator<Item = (&'q Label, State<Id>, bool, Option<FilterId>)> + '_>;
+}
+
+impl<'q, Id: StateId> AutomatonTransitions<'q, Id> for Automaton<'q, Id> {
+    #[inline]
+    fn state_count(&self) -> usize {
+        self.states.len()
+    }
+
+    #[inline]
+    fn fallback_state(&self, state: State<Id>) -> (State<Id>, bool) {
+        self[state].fallback_state()
+    }
+
+    #[inline]
+    fn transitions(&self, state: State<Id>) -> Box<dyn Iterator<Item = (&'q Label, State<Id>, bool, Option<FilterId>)> + '_> {
+        Box::new(self[state].transitions().iter().copied())
+    }
+}
+
+/// A single transition inside a [`SparseAutomaton`]'s flat transition arena.
+#[derive(Debug, Clone, Copy)]
+struct SparseTransition<'q, Id: StateId> {
+    label: &'q Label,
+    target: State<Id>,
+    is_accepting: bool,
+    filter: Option<FilterId>,
+}
+
+/// A compact, flat-array view of an [`Automaton`], built via [`Automaton::to_sparse`].
+///
+/// Every state's transitions are laid out contiguously in one arena, indexed by a
+/// parallel array of per-state offsets, so the whole automaton lives in a handful of
+/// allocations instead of one `SmallVec` per state.
+#[derive(Debug)]
+pub struct SparseAutomaton<'q, Id: StateId = u8> {
+    /// `state_offsets[i]..state_offsets[i + 1]` indexes into `transitions` for state `i`.
+    state_offsets: Vec<u32>,
+    transitions: Vec<SparseTransition<'q, Id>>,
+    fallback_states: Vec<(State<Id>, bool)>,
+}
+
+impl<'q, Id: StateId> Automaton<'q, Id> {
+    /// Build a [`SparseAutomaton`] view of this automaton.
+    ///
+    /// Flattens every state's transitions into one contiguous arena. Prefer this over
+    /// the dense representation once a minimized query is wide enough that per-state
+    /// `SmallVec` allocations stop being cache-friendly; the dense form remains the
+    /// better choice for small, everyday queries.
+    #[must_use]
+    pub fn to_sparse(&self) -> SparseAutomaton<'q, Id> {
+        let mut state_offsets = Vec::with_capacity(self.states.len() + 1);
+        let mut transitions = Vec::new();
+        let mut fallback_states = Vec::with_capacity(self.states.len());
+
+        state_offsets.push(0);
+        for table in &self.states {
+            for &(label, target, is_accepting, filter) in table.transitions().iter() {
+                transitions.push(SparseTransition {
+                    label,
+                    target,
+                    is_accepting,
+                    filter,
+                });
+            }
+            state_offsets.push(transitions.len() as u32);
+            fallback_states.push(table.fallback_state());
+        }
+
+        SparseAutomaton {
+            state_offsets,
+            transitions,
+            fallback_states,
+        }
+    }
+}
+
+impl<'q, Id: StateId> AutomatonTransitions<'q, Id> for SparseAutomaton<'q, Id> {
+    #[inline]
+    fn state_count(&self) -> usize {
+        self.fallback_states.len()
+    }
+
+    #[inline]
+    fn fallback_state(&self, state: State<Id>) -> (State<Id>, bool) {
+        self.fallback_states[state_index(state)]
+    }
+
+    #[inline]
+    fn transitions(&self, state: State<Id>) -> Box<dyn Iterator<Item = (&'q Label, State<Id>, bool, Option<FilterId>)> + '_> {
+        let idx = state_index(state);
+        let range = self.state_offsets[idx] as usize..self.state_offsets[idx + 1] as usize;
+        Box::new(
+            self.transitions[range]
+                .iter()
+                .map(|t| (t.label, t.target, t.is_accepting, t.filter)),
+        )
+    }
+}
+
+#[inline(always)]
+fn state_index<Id: StateId>(state: State<Id>) -> usize {
+    state.0.as_usize()
+}
+
+/// Breadth-first reachability from `from`, in discovery order.
+///
+/// Generic over [`AutomatonTransitions`] rather than the concrete [`Automaton`] or
+/// [`SparseAutomaton`] type, so the same walk runs over either representation
+/// unchanged -- the common indexing trait doing its job, even without an executor
+/// in this tree yet to drive it at match time.
+#[must_use]
+pub(crate) fn reachable_states<'q, Id: StateId>(
+    automaton: &impl AutomatonTransitions<'q, Id>,
+    from: State<Id>,
+) -> Vec<State<Id>> {
+    let mut visited = vec![false; automaton.state_count()];
+    let mut queue = VecDeque::new();
+    let mut order = Vec::new();
+
+    visited[state_index(from)] = true;
+    queue.push_back(from);
+
+    while let Some(state) = queue.pop_front() {
+        order.push(state);
+
+        let (fallback, _) = automaton.fallback_state(state);
+        if !visited[state_index(fallback)] {
+            visited[state_index(fallback)] = true;
+            queue.push_back(fallback);
+        }
+
+        for (_, target, _, _) in automaton.transitions(state) {
+            if !visited[state_index(target)] {
+                visited[state_index(target)] = true;
+                queue.push_back(target);
+            }
+        }
+    }
+
+    order
+}
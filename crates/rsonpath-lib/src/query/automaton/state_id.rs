@@ -0,0 +1,114 @@
+//! The integer representation backing a [`State`](super::State).
+//!
+//! Mirrors the approach `regex-automata` takes with its `StateID` parameter: the
+//! width is a type parameter of the automaton, so a `u8` automaton pays only a single
+//! byte per state while a `u16` or `u32` one can grow past the 256-state ceiling.
+//! [`Automaton::new`](super::Automaton::new) always builds the `u8` form; a wider width
+//! is used only when the caller names it through
+//! [`Automaton::new_with_id`](super::Automaton::new_with_id).
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// An integer type that can be used to index the states of an [`Automaton`](super::Automaton).
+///
+/// Implemented for `u8`, `u16`, and `u32`. [`Automaton::new`](super::Automaton::new)
+/// always uses `u8` and raises [`CompilerError::QueryTooComplex`](super::CompilerError)
+/// when the minimized state count does not fit it; no wider width is tried automatically.
+pub trait StateId: Copy + Eq + Ord + Hash + Debug + Default + 'static {
+    /// The number of distinct values this width can represent, i.e. `2^bits`.
+    const MAX_STATES: u64;
+
+    /// Convert a state index into this representation.
+    ///
+    /// # Panics
+    /// May panic (via truncation becoming incorrect) if `value >= Self::MAX_STATES`;
+    /// callers are expected to check [`StateId::MAX_STATES`] before constructing ids
+    /// of this width.
+    fn from_usize(value: usize) -> Self;
+
+    /// Convert this id back into a plain `usize` index.
+    fn as_usize(self) -> usize;
+}
+
+macro_rules! impl_state_id {
+    ($ty:ty) => {
+        impl StateId for $ty {
+            const MAX_STATES: u64 = <$ty>::MAX as u64 + 1;
+
+            #[inline(always)]
+            fn from_usize(value: usize) -> Self {
+                value as $ty
+            }
+
+            #[inline(always)]
+            fn as_usize(self) -> usize {
+                self as usize
+            }
+        }
+    };
+}
+
+impl_state_id!(u8);
+impl_state_id!(u16);
+impl_state_id!(u32);
+
+/// Pick the narrowest [`StateId`] width, as a [`StateIdWidth`] tag, that can hold
+/// `state_count` distinct states.
+///
+/// Returns `None` if `state_count` overflows even `u32`.
+#[must_use]
+pub(crate) fn narrowest_width(state_count: usize) -> Option<StateIdWidth> {
+    let state_count = state_count as u64;
+    if state_count <= u8::MAX_STATES {
+        Some(StateIdWidth::U8)
+    } else if state_count <= u16::MAX_STATES {
+        Some(StateIdWidth::U16)
+    } else if state_count <= u32::MAX_STATES {
+        Some(StateIdWidth::U32)
+    } else {
+        None
+    }
+}
+
+/// Tag identifying which concrete [`StateId`] width an [`Automaton`](super::Automaton) was
+/// minimized to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum StateIdWidth {
+    /// States fit in a [`u8`], capping the automaton at 256 states.
+    U8,
+    /// States fit in a [`u16`], capping the automaton at 65536 states.
+    U16,
+    /// States fit in a [`u32`], capping the automaton at 2^32 states.
+    U32,
+}
+
+/// A growable bitset over state indices.
+///
+/// Used by [`Automaton::accepting_states`](super::Automaton::accepting_states) in place
+/// of a fixed-width `SmallSet256`, so the accepting-set machinery scales with whichever
+/// [`StateId`] width the automaton was minimized to, instead of capping out at 256 states.
+#[derive(Debug, Default)]
+pub(crate) struct StateIdSet {
+    words: Vec<u64>,
+}
+
+impl StateIdSet {
+    /// Mark `index` as present in the set, growing the backing storage if needed.
+    pub(crate) fn insert(&mut self, index: usize) {
+        let word = index / u64::BITS as usize;
+        let bit = index % u64::BITS as usize;
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        self.words[word] |= 1 << bit;
+    }
+
+    /// Iterate over the indices present in the set, in ascending order.
+    pub(crate) fn into_iter(self) -> impl Iterator<Item = usize> {
+        self.words.into_iter().enumerate().flat_map(|(word_idx, word)| {
+            (0..u64::BITS as usize)
+                .filter(move |bit| (word >> bit) & 1 != 0)
+                .map(move |bit| word_idx * u64::BITS as usize + bit)
+        })
+    }
+}
@@ -1,11 +1,13 @@
 //! Result types that can be returned by a JSONPath query engine.
 use crate::depth::Depth;
-use std::fmt::Display;
+use core::fmt::Display;
 
 pub mod count;
 pub mod empty;
 pub mod index;
 pub mod nodes;
+pub mod sink;
+pub mod spans;
 
 /// Type of a value being reported to a [`Recorder`].
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
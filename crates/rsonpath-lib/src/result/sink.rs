@@ -0,0 +1,306 @@
+//! [`QueryResult`] and [`Recorder`] implementation that streams matches to a callback.
+//!
+//! [`nodes::NodesRecorder`](`super::nodes::NodesRecorder`) buffers every matched value into a
+//! `Vec<Vec<u8>>` and only hands them back once the whole query has finished running, which is
+//! unworkable for queries that match gigabytes of nodes. [`SinkRecorder`] reuses the exact same
+//! `PartialNode`/`PreparedNode` bookkeeping and atomic-node whitespace-trimming logic, but instead
+//! of pushing a finalized node's buffer into a `finished` vector, it hands the buffer to a
+//! user-supplied callback the moment the node is finalized and then drops it. This lets callers
+//! stream results to a writer, channel, or parser incrementally, bounding memory use to the
+//! in-flight nodes rather than the whole match set.
+#![allow(clippy::expect_used)]
+use super::*;
+use crate::{debug, depth::Depth};
+use std::{
+    cell::RefCell,
+    fmt::{self, Debug, Display},
+    str,
+};
+
+/// [`QueryResult`] produced by a [`SinkRecorder`]: whether every callback invocation succeeded.
+///
+/// Carries no matched bytes of its own &mdash; those were already handed to the callback as they
+/// were finalized. Only the first error raised by the callback, if any, is retained.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SinkResult<E>(Result<(), E>);
+
+impl<E> SinkResult<E> {
+    /// Turn this result into the underlying [`Result`], surfacing the first error raised by the
+    /// sink callback, if the callback ever returned one.
+    #[inline(always)]
+    pub fn into_result(self) -> Result<(), E> {
+        self.0
+    }
+}
+
+impl<E> Default for SinkResult<E> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self(Ok(()))
+    }
+}
+
+impl<E: Display> Display for SinkResult<E> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Ok(()) => Ok(()),
+            Err(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl<E: Display + PartialEq> QueryResult for SinkResult<E> {}
+
+/// [`Recorder`] that streams every matched node's bytes to a callback as soon as it is finalized.
+///
+/// The callback is given only a borrow of the finalized node's bytes and is invoked at most once
+/// per match, in the order in which the matched values end in the input, so a match nested
+/// inside another match is passed before the one enclosing it. Once the callback returns an error, it is no longer invoked for
+/// any later match; the error is retained and returned from [`Recorder::finish`].
+pub struct SinkRecorder<F, E> {
+    internal: RefCell<InternalRecorder<F, E>>,
+}
+
+impl<F, E> SinkRecorder<F, E>
+where
+    F: FnMut(&[u8]) -> Result<(), E>,
+{
+    /// Create a new [`SinkRecorder`] that hands finalized node bytes to `sink`.
+    #[must_use]
+    #[inline]
+    pub fn new(sink: F) -> Self {
+        Self {
+            internal: RefCell::new(InternalRecorder::new(sink)),
+        }
+    }
+}
+
+impl<F, E> InputRecorder for SinkRecorder<F, E>
+where
+    F: FnMut(&[u8]) -> Result<(), E>,
+{
+    #[inline(always)]
+    fn record_block_end(&self, new_block: &[u8]) {
+        self.internal.borrow_mut().record_block(new_block)
+    }
+}
+
+impl<F, E> Recorder for SinkRecorder<F, E>
+where
+    F: FnMut(&[u8]) -> Result<(), E>,
+    E: Display + PartialEq,
+{
+    type Result = SinkResult<E>;
+
+    #[inline]
+    fn new() -> Self {
+        panic!("SinkRecorder requires a sink callback; construct it with SinkRecorder::new")
+    }
+
+    #[inline]
+    fn record_match(&self, idx: usize, depth: Depth, ty: MatchedNodeType) {
+        debug!("Recording match at {idx}");
+        self.internal.borrow_mut().record_match(idx, depth, ty)
+    }
+
+    #[inline]
+    fn record_value_terminator(&self, idx: usize, depth: Depth) {
+        self.internal.borrow_mut().record_value_terminator(idx, depth)
+    }
+
+    #[inline]
+    fn finish(self) -> Self::Result {
+        debug!("Finish recording.");
+        self.internal.into_inner().finish()
+    }
+}
+
+struct InternalRecorder<F, E> {
+    idx: usize,
+    stack: Vec<PartialNode>,
+    ready: Vec<PreparedNode>,
+    sink: F,
+    error: Option<E>,
+}
+
+struct PartialNode {
+    start_idx: usize,
+    start_depth: Depth,
+    buf: Vec<u8>,
+    ty: MatchedNodeType,
+}
+
+struct PreparedNode {
+    start_idx: usize,
+    buf: Vec<u8>,
+    end_idx: usize,
+    ty: MatchedNodeType,
+}
+
+impl PartialNode {
+    fn prepare(self, end_idx: usize) -> PreparedNode {
+        PreparedNode {
+            start_idx: self.start_idx,
+            buf: self.buf,
+            end_idx,
+            ty: self.ty,
+        }
+    }
+}
+
+impl<F, E> InternalRecorder<F, E>
+where
+    F: FnMut(&[u8]) -> Result<(), E>,
+{
+    fn new(sink: F) -> Self {
+        Self {
+            idx: 0,
+            stack: vec![],
+            ready: vec![],
+            sink,
+            error: None,
+        }
+    }
+
+    fn record_block(&mut self, block: &[u8]) {
+        let idx = self.idx;
+        for mut top in self.ready.drain(..) {
+            debug!("Final block for {top:?} starting at {idx}");
+            Self::append_final_block(&mut top.buf, block, idx, top.start_idx, top.end_idx);
+            finalize_node(&mut self.sink, &mut self.error, top);
+        }
+
+        for node in &mut self.stack {
+            debug!("Continuing node: {node:?}, idx is {}", self.idx);
+            Self::append_block(&mut node.buf, block, self.idx, node.start_idx)
+        }
+
+        self.idx += block.len();
+
+        fn finalize_node<F, E>(sink: &mut F, error: &mut Option<E>, mut node: PreparedNode)
+        where
+            F: FnMut(&[u8]) -> Result<(), E>,
+        {
+            debug!("Finalizing node: {node:?}");
+
+            if node.ty == MatchedNodeType::Atomic {
+                // Atomic nodes are finished when the next structural character is matched.
+                // The buffer includes that character and all preceding whitespace.
+                // We need to remove it before saving the result.
+                let mut i = node.buf.len() - 2;
+                while node.buf[i] == b' ' || node.buf[i] == b'\t' || node.buf[i] == b'\n' || node.buf[i] == b'\r' {
+                    i -= 1;
+                }
+
+                node.buf.truncate(i + 1);
+            }
+
+            if error.is_some() {
+                // A previous match already failed the sink; stop invoking it, but keep
+                // the bookkeeping above intact so later matches never see half-finalized state.
+                return;
+            }
+
+            debug!("Committing node: {node:?}");
+            if let Err(err) = sink(&node.buf) {
+                *error = Some(err);
+            }
+        }
+    }
+
+    fn append_final_block(dest: &mut Vec<u8>, src: &[u8], src_start: usize, read_start: usize, read_end: usize) {
+        debug_assert!(read_end >= src_start);
+        let in_block_start = if read_start > src_start {
+            read_start - src_start
+        } else {
+            0
+        };
+        let in_block_end = read_end - src_start;
+
+        dest.extend(&src[in_block_start..in_block_end]);
+    }
+
+    fn append_block(dest: &mut Vec<u8>, src: &[u8], src_start: usize, read_start: usize) {
+        if read_start >= src_start + src.len() {
+            return;
+        }
+
+        let to_extend = if read_start > src_start {
+            let in_block_start = read_start - src_start;
+            &src[in_block_start..]
+        } else {
+            src
+        };
+
+        dest.extend(to_extend);
+    }
+
+    fn record_match(&mut self, idx: usize, depth: Depth, ty: MatchedNodeType) {
+        // In case of atomic types, any structural event that happens
+        // at or above current depth marks the end. For complex types,
+        // we first get the opening structural event, so the end is marked
+        // by a depth decrease of 1.
+        let start_depth = match ty {
+            MatchedNodeType::Atomic => (depth + 1).expect("depth not above limit"),
+            MatchedNodeType::Complex => depth,
+        };
+
+        let node = PartialNode {
+            start_idx: idx,
+            start_depth: depth,
+            buf: vec![],
+            ty,
+        };
+
+        debug!("New node {node:?}");
+        self.stack.push(node);
+    }
+
+    #[inline]
+    fn record_value_terminator(&mut self, idx: usize, depth: Depth) {
+        debug!("Value terminator at {idx}, depth {depth}");
+        while let Some(node) = self.stack.last() {
+            if node.start_depth >= depth {
+                debug!("Mark node {node:?} as ended at {}", idx + 1);
+                let node = self.stack.pop().expect("last was Some, pop must succeed");
+                let prepared_node = node.prepare(idx + 1);
+                self.ready.push(prepared_node);
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn finish(self) -> SinkResult<E> {
+        debug_assert!(self.stack.is_empty());
+
+        SinkResult(match self.error {
+            Some(err) => Err(err),
+            None => Ok(()),
+        })
+    }
+}
+
+impl Debug for PartialNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PartialNode")
+            .field("start_idx", &self.start_idx)
+            .field("start_depth", &self.start_depth)
+            .field("ty", &self.ty)
+            .field("buf", &str::from_utf8(&self.buf).unwrap_or("[invalid utf8]"))
+            .finish()
+    }
+}
+
+impl Debug for PreparedNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PartialNode")
+            .field("start_idx", &self.start_idx)
+            .field("end_idx", &self.end_idx)
+            .field("ty", &self.ty)
+            .field("buf", &str::from_utf8(&self.buf).unwrap_or("[invalid utf8]"))
+            .finish()
+    }
+}
+
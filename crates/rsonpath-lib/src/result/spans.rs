@@ -0,0 +1,316 @@
+//! [`QueryResult`] and [`Recorder`] implementation that borrows matched spans from the input.
+//!
+//! [`nodes::NodesRecorder`](`super::nodes::NodesRecorder`) copies every matched value's bytes out
+//! of the blocks it is handed, which is pure overhead when the whole input is already sitting in
+//! memory (as with `BorrowedBytes`): the bytes could just as well be borrowed back out of the
+//! original input. [`SpansRecorder`] records only the `(start_idx, end_idx)` [`Span`] of each
+//! match, still scanning the accumulated bytes of an in-flight node at finalize time to apply the
+//! same atomic trailing-whitespace correction as [`NodesRecorder`] &mdash; but once a node is
+//! finalized, only its `Span` is kept and the scanned bytes are dropped. [`Span::get`] then lets
+//! the caller borrow the matched slice directly out of the original input.
+#![allow(clippy::expect_used)]
+use super::*;
+use crate::{debug, depth::Depth};
+use std::{
+    fmt::{self, Debug, Display},
+    cell::RefCell,
+    str,
+};
+
+/// A borrowed byte range `[start, end)` of a matched value within the original input.
+///
+/// Carries no bytes of its own; [`Span::get`] borrows the matching slice out of whatever input
+/// the query was run against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Span {
+    start_idx: usize,
+    end_idx: usize,
+}
+
+impl Span {
+    /// The index of the first byte of the matched value, inclusive.
+    #[must_use]
+    #[inline(always)]
+    pub fn start_idx(&self) -> usize {
+        self.start_idx
+    }
+
+    /// The index one past the last byte of the matched value, exclusive.
+    #[must_use]
+    #[inline(always)]
+    pub fn end_idx(&self) -> usize {
+        self.end_idx
+    }
+
+    /// Borrow the bytes of this span out of `input`.
+    ///
+    /// `input` must be the same input (or an identical copy of it) the query was run against;
+    /// any byte source whose bytes can be borrowed as a slice will do.
+    #[must_use]
+    #[inline(always)]
+    pub fn get<'i>(&self, input: &'i (impl AsRef<[u8]> + ?Sized)) -> &'i [u8] {
+        &input.as_ref()[self.start_idx..self.end_idx]
+    }
+}
+
+/// [`QueryResult`] that collects the [`Span`] of every matched value; it holds no copy of the
+/// matched bytes. [`SpansRecorder`] still buffers the bytes of each match until it is finalized.
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SpansResult {
+    spans: Vec<Span>,
+}
+
+impl SpansResult {
+    /// Get the [`Span`] of every matched node, in the order in which the matched values end in
+    /// the input: a match nested inside another match comes before the one enclosing it.
+    #[must_use]
+    #[inline(always)]
+    pub fn get(&self) -> &[Span] {
+        &self.spans
+    }
+
+    /// Return the inner [`Span`] vector, consuming the result.
+    #[must_use]
+    #[inline(always)]
+    pub fn into_inner(self) -> Vec<Span> {
+        self.spans
+    }
+}
+
+impl From<SpansResult> for Vec<Span> {
+    #[inline(always)]
+    fn from(result: SpansResult) -> Self {
+        result.spans
+    }
+}
+
+impl Display for SpansResult {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for span in &self.spans {
+            writeln!(f, "[{}, {})", span.start_idx, span.end_idx)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl QueryResult for SpansResult {}
+
+/// Recorder for [`SpansResult`].
+pub struct SpansRecorder {
+    internal: RefCell<InternalRecorder>,
+}
+
+impl InputRecorder for SpansRecorder {
+    #[inline(always)]
+    fn record_block_end(&self, new_block: &[u8]) {
+        self.internal.borrow_mut().record_block(new_block)
+    }
+}
+
+impl Recorder for SpansRecorder {
+    type Result = SpansResult;
+
+    #[inline]
+    fn new() -> Self {
+        Self {
+            internal: RefCell::new(InternalRecorder::new()),
+        }
+    }
+
+    #[inline]
+    fn record_match(&self, idx: usize, depth: Depth, ty: MatchedNodeType) {
+        debug!("Recording match at {idx}");
+        self.internal.borrow_mut().record_match(idx, depth, ty)
+    }
+
+    #[inline]
+    fn record_value_terminator(&self, idx: usize, depth: Depth) {
+        self.internal.borrow_mut().record_value_terminator(idx, depth)
+    }
+
+    #[inline]
+    fn finish(self) -> Self::Result {
+        debug!("Finish recording.");
+        self.internal.into_inner().finish()
+    }
+}
+
+struct InternalRecorder {
+    idx: usize,
+    stack: Vec<PartialNode>,
+    ready: Vec<PreparedNode>,
+    finished: Vec<Span>,
+}
+
+struct PartialNode {
+    start_idx: usize,
+    start_depth: Depth,
+    // Only kept around long enough to locate the trimmed end of an atomic node at finalize
+    // time; dropped as soon as the node is finalized into a `Span`.
+    buf: Vec<u8>,
+    ty: MatchedNodeType,
+}
+
+struct PreparedNode {
+    start_idx: usize,
+    buf: Vec<u8>,
+    end_idx: usize,
+    ty: MatchedNodeType,
+}
+
+impl PartialNode {
+    fn prepare(self, end_idx: usize) -> PreparedNode {
+        PreparedNode {
+            start_idx: self.start_idx,
+            buf: self.buf,
+            end_idx,
+            ty: self.ty,
+        }
+    }
+}
+
+impl InternalRecorder {
+    fn new() -> Self {
+        Self {
+            idx: 0,
+            stack: vec![],
+            ready: vec![],
+            finished: vec![],
+        }
+    }
+
+    fn record_block(&mut self, block: &[u8]) {
+        mov(self.idx, &mut self.ready, &mut self.finished, block);
+
+        for node in &mut self.stack {
+            debug!("Continuing node: {node:?}, idx is {}", self.idx);
+            Self::append_block(&mut node.buf, block, self.idx, node.start_idx)
+        }
+
+        self.idx += block.len();
+
+        fn mov(idx: usize, ready: &mut Vec<PreparedNode>, finished: &mut Vec<Span>, block: &[u8]) {
+            for mut top in ready.drain(..) {
+                debug!("Final block for {top:?} starting at {idx}");
+                InternalRecorder::append_final_block(&mut top.buf, block, idx, top.start_idx, top.end_idx);
+                finalize_node(finished, top);
+            }
+        }
+
+        fn finalize_node(finished: &mut Vec<Span>, mut node: PreparedNode) {
+            debug!("Finalizing node: {node:?}");
+
+            if node.ty == MatchedNodeType::Atomic {
+                // Atomic nodes are finished when the next structural character is matched.
+                // The buffer includes that character and all preceding whitespace.
+                // We need to remove it before computing the trimmed end of the span.
+                let mut i = node.buf.len() - 2;
+                while node.buf[i] == b' ' || node.buf[i] == b'\t' || node.buf[i] == b'\n' || node.buf[i] == b'\r' {
+                    i -= 1;
+                }
+
+                node.buf.truncate(i + 1);
+            }
+
+            debug!("Committing span: {node:?}");
+            finished.push(Span {
+                start_idx: node.start_idx,
+                end_idx: node.start_idx + node.buf.len(),
+            });
+        }
+    }
+
+    fn append_final_block(dest: &mut Vec<u8>, src: &[u8], src_start: usize, read_start: usize, read_end: usize) {
+        debug_assert!(read_end >= src_start);
+        let in_block_start = if read_start > src_start {
+            read_start - src_start
+        } else {
+            0
+        };
+        let in_block_end = read_end - src_start;
+
+        dest.extend(&src[in_block_start..in_block_end]);
+    }
+
+    fn append_block(dest: &mut Vec<u8>, src: &[u8], src_start: usize, read_start: usize) {
+        if read_start >= src_start + src.len() {
+            return;
+        }
+
+        let to_extend = if read_start > src_start {
+            let in_block_start = read_start - src_start;
+            &src[in_block_start..]
+        } else {
+            src
+        };
+
+        dest.extend(to_extend);
+    }
+
+    fn record_match(&mut self, idx: usize, depth: Depth, ty: MatchedNodeType) {
+        // In case of atomic types, any structural event that happens
+        // at or above current depth marks the end. For complex types,
+        // we first get the opening structural event, so the end is marked
+        // by a depth decrease of 1.
+        let start_depth = match ty {
+            MatchedNodeType::Atomic => (depth + 1).expect("depth not above limit"),
+            MatchedNodeType::Complex => depth,
+        };
+
+        let node = PartialNode {
+            start_idx: idx,
+            start_depth: depth,
+            buf: vec![],
+            ty,
+        };
+
+        debug!("New node {node:?}");
+        self.stack.push(node);
+    }
+
+    #[inline]
+    fn record_value_terminator(&mut self, idx: usize, depth: Depth) {
+        debug!("Value terminator at {idx}, depth {depth}");
+        while let Some(node) = self.stack.last() {
+            if node.start_depth >= depth {
+                debug!("Mark node {node:?} as ended at {}", idx + 1);
+                let node = self.stack.pop().expect("last was Some, pop must succeed");
+                let prepared_node = node.prepare(idx + 1);
+                self.ready.push(prepared_node);
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn finish(self) -> SpansResult {
+        debug_assert!(self.stack.is_empty());
+
+        SpansResult { spans: self.finished }
+    }
+}
+
+impl Debug for PartialNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PartialNode")
+            .field("start_idx", &self.start_idx)
+            .field("start_depth", &self.start_depth)
+            .field("ty", &self.ty)
+            .field("buf", &str::from_utf8(&self.buf).unwrap_or("[invalid utf8]"))
+            .finish()
+    }
+}
+
+impl Debug for PreparedNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PartialNode")
+            .field("start_idx", &self.start_idx)
+            .field("end_idx", &self.end_idx)
+            .field("ty", &self.ty)
+            .field("buf", &str::from_utf8(&self.buf).unwrap_or("[invalid utf8]"))
+            .finish()
+    }
+}
+
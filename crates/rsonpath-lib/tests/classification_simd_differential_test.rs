@@ -0,0 +1,159 @@
+//! Differential test harness asserting every forceable [`SimdTag`] backend agrees with the
+//! `nosimd` oracle.
+//!
+//! Borrows the cross-target verification strategy the `std::simd` ("portable-SIMD") project uses
+//! for its own backend: generate random, adversarial byte buffers and drive each backend over the
+//! *same* bytes through [`simd_dispatch`], rather than relying on whatever the CPU this test
+//! happens to run on auto-detects. A classifier bug that only manifests on a specific vector
+//! width, or right at a block boundary, reproduces deterministically this way instead of only on
+//! whatever hardware a CI runner happens to have.
+use proptest::prelude::*;
+use rsonpath_lib::classification::depth::{BracketType, DepthIterator};
+use rsonpath_lib::classification::memmem::Memmem;
+use rsonpath_lib::classification::quotes::QuoteClassifiedIterator;
+use rsonpath_lib::classification::simd::{simd_dispatch, SimdConfiguration, SimdTag};
+use rsonpath_lib::classification::structural::StructuralIterator;
+use rsonpath_lib::input::{BorrowedBytes, Input, MAX_BLOCK_SIZE};
+use rsonpath_lib::query::JsonString;
+use rsonpath_lib::result::InputRecorder;
+
+/// A recorder that observes nothing; the classifiers under test don't need one to function, but
+/// every entry point requires one to thread through to the block iterator.
+struct NoopRecorder;
+
+impl<B> InputRecorder<B> for NoopRecorder {
+    fn record_block_end(&self, _new_block: &B) {}
+}
+
+/// Every tag this test knows how to force, independent of whether the host running it actually
+/// supports the backend. `Nosimd` and `Portable` never need an intrinsic, so they always apply;
+/// the rest are filtered down to whatever [`SimdTag::is_supported_on_current_target`] allows at
+/// each use site below, so a tier the CI host's CPU lacks (e.g. `Avx512` on a CI runner without
+/// AVX-512BW) is never actually forced -- doing so would execute an unsupported instruction
+/// instead of merely comparing classification output.
+const FORCEABLE_TAGS: &[SimdTag] = &[
+    SimdTag::Nosimd,
+    SimdTag::Portable,
+    SimdTag::Sse2,
+    SimdTag::Ssse3,
+    SimdTag::Avx2,
+    SimdTag::Avx512,
+    SimdTag::Neon128,
+];
+
+fn configuration_for(tag: SimdTag) -> SimdConfiguration {
+    let fast = matches!(tag, SimdTag::Avx2 | SimdTag::Avx512);
+    SimdConfiguration::builder()
+        .highest_simd(tag)
+        .fast_quotes(fast)
+        .fast_popcnt(fast)
+        .build()
+        .expect("FORCEABLE_TAGS must only contain tags valid with these flags")
+}
+
+/// Pad `bytes` up to a multiple of [`MAX_BLOCK_SIZE`] with trailing spaces, the way the engine's
+/// own padding layer does, so it can be wrapped in a [`BorrowedBytes`] without reallocating through
+/// the owned-input path.
+fn pad(mut bytes: Vec<u8>) -> Vec<u8> {
+    let remainder = bytes.len() % MAX_BLOCK_SIZE;
+    if remainder != 0 {
+        bytes.resize(bytes.len() + (MAX_BLOCK_SIZE - remainder), b' ');
+    }
+    bytes
+}
+
+/// Collect the sequence of `(within_quotes_mask, nonquoted_structural, depth_delta)` triples a
+/// backend produces over the whole input, used as the unit of comparison between backends.
+fn collect_classification(tag: SimdTag, bytes: &[u8]) -> Vec<(u64, u64, i64)> {
+    let conf = configuration_for(tag);
+    let input = unsafe { BorrowedBytes::new(bytes) };
+    let recorder = NoopRecorder;
+
+    simd_dispatch!(conf => |simd| {
+        let block_iter = input.iter_blocks(&recorder);
+        let quote_classifier = simd.classify_quoted_sequences(block_iter);
+        let mut results = Vec::new();
+
+        let structural_block_iter = input.iter_blocks(&recorder);
+        let structural_quote_classifier = simd.classify_quoted_sequences(structural_block_iter);
+        let mut structural = simd.classify_structural_characters(structural_quote_classifier);
+
+        let depth_block_iter = input.iter_blocks(&recorder);
+        let depth_quote_classifier = simd.classify_quoted_sequences(depth_block_iter);
+        let mut depth = simd.classify_depth(depth_quote_classifier, BracketType::Curly);
+
+        for quote_block in quote_classifier {
+            let structural_block = structural.next().transpose().ok().flatten();
+            let depth_block = depth.next();
+
+            let nonquoted_structural = structural_block.map_or(0, |b| b.nonquoted_structural as u64);
+            let depth_delta = depth_block.map_or(0, |b| b.get_depth());
+
+            results.push((quote_block.within_quotes_mask, nonquoted_structural, depth_delta));
+        }
+
+        results
+    })
+}
+
+proptest! {
+    /// Every forceable backend must agree with `nosimd` on quote/structural/depth classification
+    /// over the same adversarial input: unbalanced quotes, escapes straddling block boundaries,
+    /// and deep nesting are all within the generator's range.
+    #[test]
+    fn all_backends_agree_with_nosimd_oracle(
+        raw in prop::collection::vec(
+            prop::sample::select(&b"{}[]:,\"\\ abc\n"[..]),
+            0..512,
+        )
+    ) {
+        let bytes = pad(raw);
+        let oracle = collect_classification(SimdTag::Nosimd, &bytes);
+
+        for &tag in FORCEABLE_TAGS
+            .iter()
+            .filter(|&&t| t != SimdTag::Nosimd && t.is_supported_on_current_target())
+        {
+            let actual = collect_classification(tag, &bytes);
+            prop_assert_eq!(actual, oracle.clone(), "backend {:?} diverged from nosimd oracle", tag);
+        }
+    }
+
+    /// Member-label search must agree across backends on long, repeated key runs, which stress
+    /// the first-byte SIMD prefilter far more than short labels do.
+    #[test]
+    fn memmem_agrees_with_nosimd_oracle(
+        key in "[a-z]{1,16}",
+        repeats in 1usize..32,
+    ) {
+        let mut document = String::new();
+        for _ in 0..repeats {
+            document.push_str(&format!(r#"{{"{key}": 1}},"#));
+        }
+        let bytes = pad(document.into_bytes());
+        let label = JsonString::new(&key);
+
+        let oracle_conf = configuration_for(SimdTag::Nosimd);
+        let oracle_input = unsafe { BorrowedBytes::new(&bytes) };
+        let oracle_recorder = NoopRecorder;
+        let oracle_result = simd_dispatch!(oracle_conf => |simd| {
+            let mut iter = oracle_input.iter_blocks(&oracle_recorder);
+            simd.memmem(&oracle_input, &mut iter).find_label(&label)
+        });
+
+        for &tag in FORCEABLE_TAGS
+            .iter()
+            .filter(|&&t| t != SimdTag::Nosimd && t.is_supported_on_current_target())
+        {
+            let conf = configuration_for(tag);
+            let input = unsafe { BorrowedBytes::new(&bytes) };
+            let recorder = NoopRecorder;
+            let result = simd_dispatch!(conf => |simd| {
+                let mut iter = input.iter_blocks(&recorder);
+                simd.memmem(&input, &mut iter).find_label(&label)
+            });
+
+            prop_assert_eq!(result.ok(), oracle_result.as_ref().ok().copied(), "backend {:?} diverged on memmem", tag);
+        }
+    }
+}
@@ -69,10 +69,24 @@ fn main() -> Result<ExitCode, Box<dyn Error>> {
     println!("{query}");
 
     // $..book[:2]
+    // $..book[::3]
+    // $..book[3:7:2]
+    // $..book[-3:-7:-2]
+    // No builder support for slice selectors: `rsonpath-syntax` has no `src/` directory
+    // in this tree (only this examples/ file), so there is no crate for a `Slice` type
+    // or `.child_slice`/`.descendant_slice` builder methods to live in. rsonpath-lib
+    // already has a resolver for this on the other end -- see
+    // `query::ArraySelector::Slice`/`SliceSelector` in rsonpath-lib/src/query/
+    // array_selector.rs -- but nothing in this tree parses `[start:end:step]` syntax or
+    // lowers it through `automaton::nfa`/`minimizer` to produce one.
 
     // $..book[?@.isbn]
-
     // $..book[?@.price<10]
+    // No builder support for filter selectors: this example file is the only part of
+    // `rsonpath-syntax` present in this tree (there is no `src/` directory, so
+    // `JsonPathQueryBuilder` itself is only ever referenced, never defined), and
+    // `rsonpath-lib`'s `FilterExpr` evaluator (query/automaton/filter.rs) has no parser
+    // or builder producing one to call `.child_filter`/`.descendant_filter` against.
 
     // $..*
     let query = JsonPathQueryBuilder::new().descendant_wildcard().to_query();
@@ -7,7 +7,9 @@ use crate::{model, DiscoveredDocument};
 use std::{
     collections::HashMap,
     fmt::Display,
-    fs, io,
+    fs,
+    hash::{Hash, Hasher},
+    io,
     path::{Path, PathBuf},
 };
 use walkdir::WalkDir;
@@ -17,17 +19,77 @@ struct FileToWrite {
     contents: String,
 }
 
+/// Content digest of a buffered file's body, used to dedup identical writes.
+type Digest = u64;
+
+fn digest_of(contents: &str) -> Digest {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    contents.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A compression (or other content transform) applied to a generated document variant.
+///
+/// Each variant's TOML document is written to its own codec-named subdirectory of the TOML dir,
+/// with the codec and its level recorded in the document's `input`, so the same source document
+/// can be registered raw, minified, and under every supported compression scheme without the
+/// generator special-casing any one of them. No JSON body is written, minified or compressed
+/// for a variant here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Codec {
+    /// The document as originally authored, no transform applied.
+    Raw,
+    /// Whitespace stripped, otherwise byte-identical JSON.
+    Minified,
+    /// Gzip-compressed.
+    Gzip,
+    /// Zstd-compressed.
+    Zstd,
+}
+
+impl Codec {
+    fn dir_name(self) -> &'static str {
+        match self {
+            Self::Raw => "raw",
+            Self::Minified => "minified",
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    fn is_compressed(self) -> bool {
+        matches!(self, Self::Gzip | Self::Zstd)
+    }
+}
+
 /// Filesystem context.
 pub(crate) struct Files {
     json_dir: PathBuf,
     toml_dir: PathBuf,
     toml_documents: HashMap<String, DiscoveredDocument>,
     file_buf: Vec<FileToWrite>,
+    codec_stats: HashMap<Codec, CodecStats>,
+    written_bodies: HashMap<Digest, PathBuf>,
+    dedup_stats: DedupStats,
+}
+
+#[derive(Default, Clone, Copy)]
+struct CodecStats {
+    documents: usize,
+    bytes: usize,
+}
+
+#[derive(Default, Clone, Copy)]
+struct DedupStats {
+    files_deduplicated: usize,
+    bytes_saved: usize,
 }
 
 pub(crate) struct Stats {
     total_documents: usize,
     total_queries: usize,
+    codec_stats: HashMap<Codec, CodecStats>,
+    dedup_stats: DedupStats,
 }
 
 impl Stats {
@@ -38,6 +100,27 @@ impl Stats {
     pub fn number_of_queries(&self) -> usize {
         self.total_queries
     }
+
+    /// Number of document variants written under the given `codec`.
+    pub fn documents_for_codec(&self, codec: Codec) -> usize {
+        self.codec_stats.get(&codec).map_or(0, |s| s.documents)
+    }
+
+    /// Total bytes written across all variants for the given `codec`.
+    pub fn bytes_for_codec(&self, codec: Codec) -> usize {
+        self.codec_stats.get(&codec).map_or(0, |s| s.bytes)
+    }
+
+    /// Number of buffered files whose body was already on disk under another path, and so were
+    /// not rewritten.
+    pub fn files_deduplicated(&self) -> usize {
+        self.dedup_stats.files_deduplicated
+    }
+
+    /// Total bytes not rewritten to disk thanks to content-address deduplication.
+    pub fn bytes_saved_by_dedup(&self) -> usize {
+        self.dedup_stats.bytes_saved
+    }
 }
 
 impl Files {
@@ -55,6 +138,9 @@ impl Files {
             toml_dir: toml_dir.as_ref().to_path_buf(),
             toml_documents: discovery,
             file_buf: vec![],
+            codec_stats: HashMap::new(),
+            written_bodies: HashMap::new(),
+            dedup_stats: DedupStats::default(),
         })
     }
 
@@ -70,6 +156,8 @@ impl Files {
         Stats {
             total_documents,
             total_queries,
+            codec_stats: self.codec_stats.clone(),
+            dedup_stats: self.dedup_stats,
         }
     }
 
@@ -91,28 +179,6 @@ impl Files {
         }
     }
 
-    /// Register a JSON file to write that is a compressed version of the file at `original_path`.
-    pub(crate) fn add_compressed_large_json<P: AsRef<Path>>(
-        &mut self,
-        original_path: P,
-        json_string: String,
-    ) -> PathBuf {
-        let file_name = original_path
-            .as_ref()
-            .file_name()
-            .expect("all documents should have a file path");
-        let mut new_path = self.compressed_large_json_dir();
-        new_path.push(file_name);
-        new_path.set_extension("json");
-
-        self.file_buf.push(FileToWrite {
-            full_path: new_path.clone(),
-            contents: json_string,
-        });
-
-        new_path
-    }
-
     /// Register a JSON file to write that is a copy of the inline json string in the `doc`.
     pub(crate) fn add_json_source(&mut self, doc: &DiscoveredDocument, json_string: String) -> PathBuf {
         let file_name = doc
@@ -135,49 +201,74 @@ impl Files {
         new_path
     }
 
-    /// Register a TOML file to write that is a version of an existing TOML file but with compressed input.
-    pub(crate) fn add_compressed_document<P: AsRef<Path>>(
+    /// Register a new benchmark variant of `doc` under the given `codec`, writing a copy of the
+    /// TOML document (with `input.codec`/`input.codec_level` updated to match) into a
+    /// codec-named subdirectory, alongside accounting in [`Stats`].
+    ///
+    /// Replaces the old `add_compressed_document`/`add_compressed_large_json` pair: any codec,
+    /// including [`Codec::Raw`] and [`Codec::Minified`], goes through this single path now.
+    pub(crate) fn register_variant<P: AsRef<Path>>(
         &mut self,
         relative_path: P,
         name: String,
-        compressed_doc: model::Document,
+        codec: Codec,
+        level: Option<u32>,
+        mut variant_doc: model::Document,
     ) -> PathBuf {
         let file_name = relative_path
             .as_ref()
             .file_name()
             .expect("toml document must have a file name");
-        let new_dir_path = self.compressed_toml_dir();
+        let new_dir_path = Path::join(&self.toml_dir, codec.dir_name());
         let new_path = Path::join(&new_dir_path, file_name);
 
+        variant_doc.input.is_compressed = codec.is_compressed();
+        variant_doc.input.codec = codec.dir_name().to_owned();
+        variant_doc.input.codec_level = level;
+
+        let contents = model::serialize(&variant_doc);
+        let bytes = contents.len();
+
         self.file_buf.push(FileToWrite {
             full_path: new_path.clone(),
-            contents: model::serialize(&compressed_doc),
+            contents,
         });
         let new_doc = DiscoveredDocument {
-            document: compressed_doc,
-            name: format!("compressed/{}", name),
+            document: variant_doc,
+            name: format!("{}/{}", codec.dir_name(), name),
             relative_path: new_path.clone(),
         };
         self.toml_documents.insert(new_doc.name.clone(), new_doc);
 
+        let entry = self.codec_stats.entry(codec).or_default();
+        entry.documents += 1;
+        entry.bytes += bytes;
+
         new_path
     }
 
     /// Write all registered files to the filesystem.
+    ///
+    /// Before writing, each buffered file's contents are digested; if an identical body was
+    /// already written (under this call or an earlier one) the write is skipped and the
+    /// duplicate is pointed at the canonical path instead, with the savings tracked in
+    /// [`Stats`].
     pub(crate) fn flush(&mut self) -> Result<(), io::Error> {
         for file_to_write in self.file_buf.drain(..) {
-            write_file(file_to_write.full_path, file_to_write.contents)?;
-        }
+            let digest = digest_of(&file_to_write.contents);
 
-        Ok(())
-    }
+            if let Some(canonical_path) = self.written_bodies.get(&digest) {
+                self.dedup_stats.files_deduplicated += 1;
+                self.dedup_stats.bytes_saved += file_to_write.contents.len();
+                copy_file(canonical_path, &file_to_write.full_path)?;
+                continue;
+            }
 
-    fn compressed_large_json_dir(&self) -> PathBuf {
-        Path::join(&self.json_dir, "large/compressed")
-    }
+            write_file(&file_to_write.full_path, &file_to_write.contents)?;
+            self.written_bodies.insert(digest, file_to_write.full_path);
+        }
 
-    fn compressed_toml_dir(&self) -> PathBuf {
-        Path::join(&self.toml_dir, "compressed")
+        Ok(())
     }
 }
 
@@ -222,6 +313,22 @@ fn write_file<P: AsRef<Path>, D: Display>(path: P, contents: D) -> Result<(), io
     fs::write(path, contents.to_string())
 }
 
+/// Materialize `new_path` as a copy of the already-written `canonical_path`, preferring a
+/// hardlink so duplicate variants don't actually double disk usage.
+fn copy_file<P1: AsRef<Path>, P2: AsRef<Path>>(canonical_path: P1, new_path: P2) -> Result<(), io::Error> {
+    create_parent_dirs(&new_path)?;
+
+    println!(
+        "linking {} -> {}...",
+        new_path.as_ref().to_string_lossy(),
+        canonical_path.as_ref().to_string_lossy()
+    );
+    match fs::hard_link(&canonical_path, &new_path) {
+        Ok(()) => Ok(()),
+        Err(_) => fs::copy(canonical_path, new_path).map(|_| ()),
+    }
+}
+
 fn create_parent_dirs<P: AsRef<Path>>(path: P) -> Result<(), io::Error> {
     let dir = path.as_ref().parent().expect("generated files must have a parent");
     fs::create_dir_all(dir)
@@ -9,6 +9,7 @@ use rsonpath_lib::engine::{Compiler, Engine};
 use rsonpath_lib::input::{BufferedInput, Input, MmapInput};
 use rsonpath_lib::query::automaton::Automaton;
 use rsonpath_lib::query::JsonPathQuery;
+use rsonpath_lib::result::nodes::NodesResult;
 use rsonpath_lib::result::{CountResult, IndexResult, QueryResult};
 use simple_logger::SimpleLogger;
 use std::fs;
@@ -39,9 +40,12 @@ struct Args {
     /// Include verbose debug information.
     #[clap(short, long)]
     verbose: bool,
-    /// TODO: REMOVE
-    #[clap(short, long, default_value_t = false)]
-    use_mmap: bool,
+    /// Input reading strategy.
+    ///
+    /// `auto` memory-maps regular files at or above a size threshold and buffers everything
+    /// else (small files, pipes, stdin); `mmap`/`buffered` force one strategy regardless.
+    #[clap(long, value_enum, default_value_t = InputModeArg::Auto)]
+    input: InputModeArg,
     /// Engine to use for evaluating the query.
     #[clap(short, long, value_enum, default_value_t = EngineArg::Main)]
     engine: EngineArg,
@@ -52,11 +56,32 @@ struct Args {
     #[arg(conflicts_with = "engine")]
     #[arg(conflicts_with = "file_path")]
     compile: bool,
+    /// Output format used by `--compile`.
+    #[clap(long, value_enum, default_value_t = CompileFormatArg::Display)]
+    format: CompileFormatArg,
     /// Result reporting mode.
     #[clap(short, long, value_enum, default_value_t = ResultArg::Bytes)]
     result: ResultArg,
 }
 
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum CompileFormatArg {
+    /// Ad-hoc human-readable form.
+    Display,
+    /// Graphviz DOT, pipeable into `dot -Tsvg` for visual inspection.
+    Dot,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum InputModeArg {
+    /// Memory-map regular files at or above [`MMAP_THRESHOLD_BYTES`], buffer everything else.
+    Auto,
+    /// Always memory-map the input file. Only valid with a FILE_PATH argument.
+    Mmap,
+    /// Always stream the input through a growable in-memory buffer.
+    Buffered,
+}
+
 #[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
 enum EngineArg {
     /// Main SIMD-optimized iterative engine.
@@ -75,6 +100,8 @@ enum ResultArg {
     Bytes,
     /// Return only the number of matches.
     Count,
+    /// Return the full, newline-delimited contents of all matched nodes.
+    Nodes,
 }
 
 fn main() -> Result<()> {
@@ -87,36 +114,71 @@ fn main() -> Result<()> {
     run_with_args(&args).map_err(|err| err.with_note(|| format!("Query string: '{}'.", args.query.dimmed())))
 }
 
+/// Regular files at or above this size are memory-mapped by [`InputModeArg::Auto`]; smaller
+/// files pay relatively more in page-fault overhead than they save versus a buffered read.
+const MMAP_THRESHOLD_BYTES: u64 = 1 << 20;
+
 fn run_with_args(args: &Args) -> Result<()> {
     let query = parse_query(&args.query)?;
     info!("Preparing query: `{query}`\n");
 
     if args.compile {
-        compile(&query)
-    } else if args.use_mmap {
-        let file = fs::File::open(args.file_path.as_ref().unwrap())?;
-        let input = unsafe { MmapInput::map_file(&file) }?;
-
-        match args.result {
-            ResultArg::Bytes => run::<IndexResult, _>(&query, &input, args.engine),
-            ResultArg::Count => run::<CountResult, _>(&query, &input, args.engine),
+        return compile(&query, args.format);
+    }
+
+    match &args.file_path {
+        Some(file_path) => {
+            let file = fs::File::open(file_path).wrap_err("Opening the input file failed.")?;
+
+            if should_mmap(args.input, &file)? {
+                let input = unsafe { MmapInput::map_file(&file) }?;
+                run_for_result(&query, &input, args)
+            } else {
+                let input = BufferedInput::new(file);
+                run_for_result(&query, &input, args)
+            }
+        }
+        None => {
+            if args.input == InputModeArg::Mmap {
+                return Err(eyre!("`--input mmap` requires a FILE_PATH argument."));
+            }
+            let input = BufferedInput::new(std::io::stdin());
+            run_for_result(&query, &input, args)
         }
-    } else {
-        let contents = get_contents(args.file_path.as_deref())?;
-        let input = BufferedInput::new(ReadString(contents, 0));
+    }
+}
 
-        match args.result {
-            ResultArg::Bytes => run::<IndexResult, _>(&query, &input, args.engine),
-            ResultArg::Count => run::<CountResult, _>(&query, &input, args.engine),
+/// Decide whether to memory-map `file`, per the `--input` mode.
+///
+/// # Errors
+/// Propagates an error if querying the file's metadata fails.
+fn should_mmap(mode: InputModeArg, file: &fs::File) -> Result<bool> {
+    Ok(match mode {
+        InputModeArg::Mmap => true,
+        InputModeArg::Buffered => false,
+        InputModeArg::Auto => {
+            let metadata = file.metadata().wrap_err("Reading input file metadata failed.")?;
+            metadata.is_file() && metadata.len() >= MMAP_THRESHOLD_BYTES
         }
+    })
+}
+
+fn run_for_result<I: Input>(query: &JsonPathQuery, input: &I, args: &Args) -> Result<()> {
+    match args.result {
+        ResultArg::Bytes => run::<IndexResult, _>(query, input, args.engine),
+        ResultArg::Count => run::<CountResult, _>(query, input, args.engine),
+        ResultArg::Nodes => run::<NodesResult, _>(query, input, args.engine),
     }
 }
 
-fn compile(query: &JsonPathQuery) -> Result<()> {
+fn compile(query: &JsonPathQuery, format: CompileFormatArg) -> Result<()> {
     let automaton = Automaton::new(query)
         .map_err(|err| report_compiler_error(query, err).wrap_err("Error compiling the query."))?;
     info!("Automaton: {automaton}");
-    println!("{automaton}");
+    match format {
+        CompileFormatArg::Display => println!("{automaton}"),
+        CompileFormatArg::Dot => println!("{}", automaton.dot()),
+    }
     Ok(())
 }
 
@@ -166,39 +228,9 @@ fn parse_query(query_string: &str) -> Result<JsonPathQuery> {
         .map_err(|err| report_parser_error(query_string, err).wrap_err("Could not parse JSONPath query."))
 }
 
-fn get_contents(file_path: Option<&str>) -> Result<String> {
-    use std::io::{self, Read};
-    match file_path {
-        Some(path) => fs::read_to_string(path).wrap_err("Reading from file failed."),
-        None => {
-            let mut result = String::new();
-            io::stdin()
-                .read_to_string(&mut result)
-                .wrap_err("Reading from stdin failed.")?;
-            Ok(result)
-        }
-    }
-}
-
 fn configure_logger(verbose: bool) -> Result<()> {
     SimpleLogger::new()
         .with_level(if verbose { LevelFilter::Trace } else { LevelFilter::Warn })
         .init()
         .wrap_err("Logger configuration error.")
 }
-
-struct ReadString(String, usize);
-
-impl std::io::Read for ReadString {
-    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        let rem = self.0.as_bytes().len() - self.1;
-        if rem > 0 {
-            let size = std::cmp::min(1024, rem);
-            buf[..size].copy_from_slice(&self.0.as_bytes()[self.1..self.1 + size]);
-            self.1 += size;
-            Ok(size)
-        } else {
-            Ok(0)
-        }
-    }
-}